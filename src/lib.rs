@@ -1,23 +1,235 @@
-use std::collections::vec_deque::Drain;
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::collections::vec_deque::{Drain, IntoIter};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::iter::Peekable;
+use core::cmp::{Ordering, Reverse};
+use core::fmt::{self, Debug, Formatter};
+use core::mem::{self, size_of};
 use core::time::Duration;
-use std::marker::PhantomData;
-use std::ops::RangeBounds;
+use std::ops::{AddAssign, Bound, Range, RangeBounds};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Arc;
 
 type Item<T> = (Duration, T);
 
 pub type TimeVecItem<T> = Item<T>;
 
-#[derive(Clone, Debug)]
+/// Unwind-safe so that `TimeVec<T>` stays `UnwindSafe` and `RefUnwindSafe`.
+pub type Sink<T> = Box<dyn FnMut(Item<T>) + Send + Sync + UnwindSafe + RefUnwindSafe>;
+
+pub type Policy<T> = Arc<dyn EvictPolicy<T> + Send + Sync + UnwindSafe + RefUnwindSafe>;
+
+pub trait EvictPolicy<T> {
+    /// Returns how many items to evict from the front of `buffer` when `new_back` is about
+    /// to be pushed. `buffer` does not hold `new_back` yet, so it is never evicted.
+    fn should_evict(&self, buffer: &VecDeque<Item<T>>, new_back: Duration) -> usize;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimeLimit(pub Duration);
+
+impl<T> EvictPolicy<T> for TimeLimit {
+    #[inline]
+    fn should_evict(&self, buffer: &VecDeque<Item<T>>, new_back: Duration) -> usize {
+        let timestamp = new_back.saturating_sub(self.0);
+        buffer.partition_point(|i| i.0 < timestamp)
+    }
+}
+
+#[inline]
+fn lerp(before: &Item<f64>, after: &Item<f64>, at: Duration) -> f64 {
+    if before.0 == after.0 {
+        return before.1;
+    }
+    let fraction = (at - before.0).as_secs_f64() / (after.0 - before.0).as_secs_f64();
+    before.1 + (after.1 - before.1) * fraction
+}
+
+fn pearson<I: Iterator<Item = (f64, f64)>>(pairs: I) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = pairs.collect();
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let (cov, var_x, var_y) = pairs.iter().fold((0.0, 0.0, 0.0), |(cov, var_x, var_y), (x, y)| {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        (cov + dx * dy, var_x + dx * dx, var_y + dy * dy)
+    });
+
+    (var_x > 0.0 && var_y > 0.0).then(|| cov / (var_x * var_y).sqrt())
+}
+
+fn median_of(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some((values[middle - 1] + values[middle]) / 2.0)
+    } else {
+        Some(values[middle])
+    }
+}
+
+#[inline]
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+}
+
+#[inline]
+fn bucket_start(origin: Duration, timestamp: Duration, interval: Duration) -> Duration {
+    timestamp - duration_from_nanos((timestamp - origin).as_nanos() % interval.as_nanos())
+}
+
+/// Means of sorted samples per `interval`, with buckets aligned to the first timestamp.
+fn bucket_means<I: Iterator<Item = (Duration, f64)>>(iter: I, interval: Duration) -> Vec<(Duration, f64)> {
+    assert!(!interval.is_zero(), "Interval must be non-zero.");
+
+    let mut result = Vec::new();
+    let mut origin: Option<Duration> = None;
+    let mut current: Option<(Duration, f64, usize)> = None;
+    for (timestamp, value) in iter {
+        let start = bucket_start(*origin.get_or_insert(timestamp), timestamp, interval);
+        match current.as_mut() {
+            Some((bucket, sum, count)) if *bucket == start => {
+                *sum += value;
+                *count += 1;
+            }
+            _ => {
+                if let Some((bucket, sum, count)) = current.replace((start, value, 1)) {
+                    result.push((bucket, sum / count as f64));
+                }
+            }
+        }
+    }
+    if let Some((bucket, sum, count)) = current {
+        result.push((bucket, sum / count as f64));
+    }
+
+    result
+}
+
+/// Monotonic deques of `(timestamp, value)` candidates for the running min and max.
+/// A stale tracker is rebuilt on the next push.
+#[derive(Clone)]
+struct Extremes<T> {
+    value: fn(&T) -> f64,
+    min: VecDeque<(Duration, f64)>,
+    max: VecDeque<(Duration, f64)>,
+    stale: bool,
+}
+
+impl<T> Extremes<T> {
+    fn from_buffer(value: fn(&T) -> f64, buffer: &VecDeque<Item<T>>) -> Self {
+        let mut extremes = Self { value, min: VecDeque::new(), max: VecDeque::new(), stale: false };
+        buffer.iter().for_each(|item| extremes.push(item));
+        extremes
+    }
+
+    fn invalidate(&mut self) {
+        self.min.clear();
+        self.max.clear();
+        self.stale = true;
+    }
+
+    fn push(&mut self, item: &Item<T>) {
+        let value = (self.value)(&item.1);
+        // Like `f64::min`/`f64::max`, NaN never wins over a number.
+        if value.is_nan() {
+            return;
+        }
+        while self.min.back().is_some_and(|m| m.1 >= value) {
+            self.min.pop_back();
+        }
+        while self.max.back().is_some_and(|m| m.1 <= value) {
+            self.max.pop_back();
+        }
+        self.min.push_back((item.0, value));
+        self.max.push_back((item.0, value));
+    }
+
+    fn evict_before(&mut self, front: Option<Duration>) {
+        let front = front.unwrap_or(Duration::MAX);
+        while self.min.front().is_some_and(|m| m.0 < front) {
+            self.min.pop_front();
+        }
+        while self.max.front().is_some_and(|m| m.0 < front) {
+            self.max.pop_front();
+        }
+    }
+}
+
+/// K-way merge of sorted parts by timestamp. Items that are not newer than the last
+/// yielded one are skipped, so on equal timestamps the item from the earlier part is kept.
+struct MergeParts<T> {
+    iters: Vec<Peekable<IntoIter<Item<T>>>>,
+    heap: BinaryHeap<Reverse<(Duration, usize)>>,
+    last: Option<Duration>,
+}
+
+impl<T> MergeParts<T> {
+    fn new<I: IntoIterator<Item = TimeVec<T>>>(parts: I) -> Self {
+        let mut iters: Vec<_> = parts
+            .into_iter()
+            .map(|part| part.buffer.into_iter().peekable())
+            .collect();
+
+        let mut heap = BinaryHeap::with_capacity(iters.len());
+        for (index, iter) in iters.iter_mut().enumerate() {
+            if let Some(item) = iter.peek() {
+                heap.push(Reverse((item.0, index)));
+            }
+        }
+
+        Self { iters, heap, last: None }
+    }
+}
+
+impl<T> Iterator for MergeParts<T> {
+    type Item = Item<T>;
+
+    fn next(&mut self) -> Option<Item<T>> {
+        while let Some(Reverse((_, index))) = self.heap.pop() {
+            let Some((timestamp, item)) = self.iters[index].next() else {
+                continue;
+            };
+            if let Some(next) = self.iters[index].peek() {
+                self.heap.push(Reverse((next.0, index)));
+            }
+            if self.last.is_some_and(|last| timestamp <= last) {
+                continue;
+            }
+            self.last = Some(timestamp);
+            return Some((timestamp, item));
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.iters.iter().map(ExactSizeIterator::len).sum()))
+    }
+}
+
 pub struct TimeVec<T> {
     pub limit: Duration,
     buffer: VecDeque<Item<T>>,
+    sink: Option<Sink<T>>,
+    policy: Option<Policy<T>>,
+    evicted: u64,
+    strict: bool,
+    extremes: Option<Extremes<T>>,
 }
 
 impl<T> TimeVec<T> {
     pub fn new(limit: Duration, capacity: usize) -> Self {
         let buffer = VecDeque::with_capacity(capacity);
-        Self { limit, buffer }
+        Self { limit, buffer, sink: None, policy: None, evicted: 0, strict: false, extremes: None }
     }
 
     pub fn builder() -> TimeVecBuilder<T> {
@@ -28,7 +240,7 @@ impl<T> TimeVec<T> {
     pub fn checked_duration(&self) -> Option<Duration> {
         self.buffer
             .front()
-            .map(|oldest| self.buffer.back().unwrap().0 - oldest.0)
+            .and_then(|oldest| self.buffer.back().unwrap().0.checked_sub(oldest.0))
     }
 
     #[inline]
@@ -45,46 +257,156 @@ impl<T> TimeVec<T> {
     }
 
     #[inline]
-    pub fn push_back_checked(&mut self, timestamp: Duration, item: T) -> Option<Drain<Item<T>>> {
-        self.timestamp_is_ok(timestamp).then(|| {
+    pub fn push_back_checked(&mut self, timestamp: Duration, item: T) -> Option<Drain<'_, Item<T>>> {
+        (self.timestamp_is_ok(timestamp) && !self.rejects_all()).then(|| {
             self.push_back_unchecked(timestamp, item)
         })
     }
 
     #[inline]
-    pub fn push_back(&mut self, timestamp: Duration, item: T) -> Drain<Item<T>> {
+    pub fn push_back(&mut self, timestamp: Duration, item: T) -> Drain<'_, Item<T>> {
         assert!(self.timestamp_is_ok(timestamp), "Timestamp is older then previous.");
         self.push_back_unchecked(timestamp, item)
     }
 
+    /// Pushes without checking that `timestamp` is newer than the back.
+    /// Out of order items break eviction and lookups; see `verify`.
     #[inline]
-    pub fn push_back_unchecked(&mut self, timestamp: Duration, item: T) -> Drain<Item<T>> {
+    pub fn push_back_unchecked(&mut self, timestamp: Duration, item: T) -> Drain<'_, Item<T>> {
+        if self.rejects_all() {
+            return self.hand_back(Some((timestamp, item)));
+        }
+
+        let count = self.push_eviction_count(timestamp);
         self.buffer.push_back((timestamp, item));
+        self.track_pushed(self.buffer.len() - 1);
+        self.evict_front(count)
+    }
+
+    /// A strict window with a zero limit stores nothing.
+    #[inline]
+    fn rejects_all(&self) -> bool {
+        self.strict && self.limit.is_zero()
+    }
+
+    /// Returns rejected items through a drain without counting them as evicted,
+    /// tracking them or calling the sink.
+    #[inline]
+    fn hand_back<I: IntoIterator<Item = Item<T>>>(&mut self, items: I) -> Drain<'_, Item<T>> {
+        let len = self.buffer.len();
+        self.buffer.extend(items);
+        self.buffer.drain(len..)
+    }
+
+    /// Items to evict before pushing `new_back`, by the policy or the time limit.
+    #[inline]
+    fn push_eviction_count(&self, new_back: Duration) -> usize {
+        let count = match self.policy.as_ref() {
+            Some(policy) => policy.should_evict(&self.buffer, new_back),
+            None => TimeLimit(self.limit).should_evict(&self.buffer, new_back),
+        };
+        count.min(self.buffer.len())
+    }
+
+    /// Evicts once after a bulk insert, as a push of the current back would.
+    #[inline]
+    fn evict_for_back(&mut self) -> Drain<'_, Item<T>> {
+        let count = match self.buffer.pop_back() {
+            Some(back) => {
+                let count = self.push_eviction_count(back.0);
+                self.buffer.push_back(back);
+                count
+            }
+            None => 0,
+        };
+        self.evict_front(count)
+    }
+
+    #[inline]
+    fn evict_older_than(&mut self, timestamp: Duration) -> Drain<'_, Item<T>> {
+        let partition_point = self.buffer.partition_point(|i| i.0 < timestamp);
+        self.evict_front(partition_point)
+    }
+
+    #[inline]
+    fn evict_front(&mut self, count: usize) -> Drain<'_, Item<T>> {
+        let count = count.min(self.buffer.len());
+        self.evicted += count as u64;
+        if let Some(extremes) = self.extremes.as_mut() {
+            extremes.evict_before(self.buffer.get(count).map(|i| i.0));
+        }
+        if let Some(sink) = self.sink.as_mut() {
+            self.buffer.drain(0..count).for_each(sink);
+            self.buffer.drain(0..0)
+        } else {
+            self.buffer.drain(0..count)
+        }
+    }
+
+    /// Feeds the items from `start` to the back into the extremes tracker.
+    #[inline]
+    fn track_pushed(&mut self, start: usize) {
+        match self.extremes.as_mut() {
+            Some(extremes) if extremes.stale => self.rebuild_extremes(),
+            Some(extremes) => self.buffer.range(start..).for_each(|item| extremes.push(item)),
+            None => {}
+        }
+    }
+
+    /// Updates the extremes tracker before `range` is removed from the buffer.
+    #[inline]
+    fn untrack_removed(&mut self, range: Range<usize>) {
+        if let Some(extremes) = self.extremes.as_mut() {
+            if range.start == 0 {
+                extremes.evict_before(self.buffer.get(range.end).map(|i| i.0));
+            } else if range.start < range.end {
+                extremes.invalidate();
+            }
+        }
+    }
 
-        let partition_timestamp = timestamp.saturating_sub(self.limit);
-        let partition_point = self.buffer.partition_point(|i| i.0 < partition_timestamp);
+    #[inline]
+    fn rebuild_extremes(&mut self) {
+        if let Some(extremes) = self.extremes.as_mut() {
+            *extremes = Extremes::from_buffer(extremes.value, &self.buffer);
+        }
+    }
 
-        self.buffer.drain(0..partition_point)
+    #[inline]
+    fn invalidate_extremes(&mut self) {
+        if let Some(extremes) = self.extremes.as_mut() {
+            extremes.invalidate();
+        }
     }
 
     #[inline]
     pub fn pop_front(&mut self) -> Option<Item<T>> {
+        self.untrack_removed(0..1);
         self.buffer.pop_front()
     }
 
     #[inline]
     pub fn pop_back(&mut self) -> Option<Item<T>> {
+        self.invalidate_extremes();
         self.buffer.pop_back()
     }
 
     #[inline]
     pub fn duration_from_back(&self, duration: &Duration) -> Option<Duration> {
-        self.buffer.back().map(|item| duration.checked_sub(item.0)).flatten()
+        self.buffer.back().and_then(|item| duration.checked_sub(item.0))
+    }
+
+    #[inline]
+    pub fn since_last(&self) -> Option<Duration> {
+        let mut iter = self.buffer.iter().rev();
+        let last = iter.next()?;
+        iter.next().map(|previous| last.0 - previous.0)
     }
 
     #[inline]
     pub fn clear(&mut self) {
-        self.buffer.clear()
+        self.buffer.clear();
+        self.rebuild_extremes();
     }
 
     #[inline]
@@ -93,17 +415,17 @@ impl<T> TimeVec<T> {
     }
 
     #[inline]
-    pub fn iter<'a>(&'a self) -> impl ExactSizeIterator<Item = &Item<T>> + 'a {
+    pub fn iter<'a>(&'a self) -> impl ExactSizeIterator<Item = &'a Item<T>> + 'a {
         self.buffer.iter()
     }
 
     #[inline]
-    pub fn iter_data<'a>(&'a self) -> impl ExactSizeIterator<Item = &T> + 'a {
+    pub fn iter_data<'a>(&'a self) -> impl ExactSizeIterator<Item = &'a T> + 'a {
         self.buffer.iter().map(|snap| &snap.1)
     }
 
     #[inline]
-    pub fn iter_time<'a>(&'a self) -> impl ExactSizeIterator<Item = &Duration> + 'a {
+    pub fn iter_time<'a>(&'a self) -> impl ExactSizeIterator<Item = &'a Duration> + 'a {
         self.buffer.iter().map(|snap| &snap.0)
     }
 
@@ -113,127 +435,4013 @@ impl<T> TimeVec<T> {
     }
 
     #[inline]
-    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<Item<T>> {
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, Item<T>> {
+        let start = match range.start_bound() {
+            Bound::Included(i) => *i,
+            Bound::Excluded(i) => i.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(i) => i.saturating_add(1),
+            Bound::Excluded(i) => *i,
+            Bound::Unbounded => self.buffer.len(),
+        };
+        self.untrack_removed(start..end);
         self.buffer.drain(range)
     }
-}
 
-#[derive(Copy, Clone, Debug)]
-pub struct TimeVecBuilder<T> {
-    pub limit: Option<Duration>,
-    pub capacity: Option<usize>,
-    _item: PhantomData<T>,
-}
+    #[inline]
+    pub fn clamp_to_limit(&mut self) -> Drain<'_, Item<T>> {
+        self.trim_to_coverage(self.limit)
+    }
 
-impl<T> Default for TimeVecBuilder<T> {
-    fn default() -> Self {
-        Self { limit: None, capacity: None, _item: PhantomData }
+    #[inline]
+    pub fn trim_to_coverage(&mut self, window: Duration) -> Drain<'_, Item<T>> {
+        let timestamp = self.buffer
+            .back()
+            .map(|i| i.0.saturating_sub(window))
+            .unwrap_or_default();
+        self.evict_older_than(timestamp)
     }
-}
 
-impl<T> TimeVecBuilder<T> {
-    pub fn with_limit(mut self, value: Duration) -> Self {
-        self.limit = Some(value);
-        self
+    #[inline]
+    pub fn push_if<F>(&mut self, timestamp: Duration, item: T, pred: F) -> Option<Drain<'_, Item<T>>>
+    where
+        F: FnOnce(Option<&Item<T>>) -> bool,
+    {
+        if pred(self.buffer.back()) {
+            self.push_back_checked(timestamp, item)
+        } else {
+            None
+        }
     }
 
-    pub fn with_limit_secs(self, value: u64) -> Self {
-        self.with_limit(Duration::from_secs(value))
+    #[inline]
+    pub fn axis_range(&self) -> Option<(Duration, Duration)> {
+        self.buffer
+            .back()
+            .map(|i| (i.0.saturating_sub(self.limit), i.0))
     }
 
-    pub fn with_limit_micros(self, value: u64) -> Self {
-        self.with_limit(Duration::from_micros(value))
+    /// Returns the oldest and the newest items. A single item is returned as both.
+    #[inline]
+    pub fn front_back(&self) -> Option<(&Item<T>, &Item<T>)> {
+        self.buffer.front().zip(self.buffer.back())
     }
 
-    pub fn with_limit_millis(self, value: u64) -> Self {
-        self.with_limit(Duration::from_millis(value))
+    #[inline]
+    pub fn is_monotonic(&self) -> bool {
+        self.verify().is_ok()
     }
 
-    pub fn with_limit_nanos(self, value: u64) -> Self {
-        self.with_limit(Duration::from_nanos(value))
+    /// Returns the index of the first item that is not newer than its predecessor.
+    pub fn verify(&self) -> Result<(), usize> {
+        match self.buffer
+            .iter()
+            .zip(self.buffer.iter().skip(1))
+            .position(|(a, b)| a.0 >= b.0)
+        {
+            Some(index) => Err(index + 1),
+            None => Ok(()),
+        }
     }
 
-    pub fn with_capacity(mut self, value: usize) -> Self {
-        self.capacity = Some(value);
-        self
+    pub fn to_pairs_with<F: Fn(&T) -> f64>(&self, f: F) -> Vec<(f64, f64)> {
+        self.buffer
+            .iter()
+            .map(|item| (item.0.as_secs_f64(), f(&item.1)))
+            .collect()
     }
 
-    pub fn build(self) -> TimeVec<T> {
-        TimeVec {
-            limit: self.limit.unwrap_or_default(),
-            buffer: self.capacity
-                .map(|value| VecDeque::<Item<T>>::with_capacity(value))
-                .unwrap_or_default()
-                
+    #[inline]
+    pub fn evict_now(&mut self, now: Duration) -> Drain<'_, Item<T>> {
+        self.evict_older_than(now.saturating_sub(self.limit))
+    }
+
+    #[inline]
+    pub fn capacity_for(limit: Duration, rate_hz: f64) -> usize {
+        if rate_hz.is_finite() && rate_hz > 0.0 {
+            (limit.as_secs_f64() * rate_hz).ceil() as usize
+        } else {
+            0
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::Duration;
+    #[inline]
+    pub fn iter_from<'a>(&'a self, after: Duration) -> impl ExactSizeIterator<Item = &'a Item<T>> + 'a {
+        let partition_point = self.buffer.partition_point(|i| i.0 <= after);
+        self.buffer.range(partition_point..)
+    }
 
-    #[test]
-    fn with_zero_limit() {
-        let mut tv = TimeVec::<()>::builder()
-            .with_limit(Duration::ZERO)
-            .build();
-        assert!(tv.is_empty());
+    #[inline]
+    fn index_range<R: RangeBounds<Duration>>(&self, range: R) -> Range<usize> {
+        let start = match range.start_bound() {
+            Bound::Included(t) => self.buffer.partition_point(|i| i.0 < *t),
+            Bound::Excluded(t) => self.buffer.partition_point(|i| i.0 <= *t),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(t) => self.buffer.partition_point(|i| i.0 <= *t),
+            Bound::Excluded(t) => self.buffer.partition_point(|i| i.0 < *t),
+            Bound::Unbounded => self.buffer.len(),
+        };
+        start..end.max(start)
+    }
 
-        assert_eq!(tv.checked_duration(), None);
-        assert_eq!(tv.duration(), Duration::ZERO);
-        assert_eq!(tv.len(), 0);
+    #[inline]
+    pub fn range<'a, R>(&'a self, range: R) -> impl ExactSizeIterator<Item = &'a Item<T>> + 'a
+    where
+        R: RangeBounds<Duration>,
+    {
+        self.buffer.range(self.index_range(range))
+    }
 
-        tv.push_back(Duration::from_secs(1), ());
-        assert_eq!(tv.checked_duration(), Some(Duration::ZERO));
-        assert_eq!(tv.duration(), Duration::ZERO);
-        assert_eq!(tv.len(), 1);
+    #[inline]
+    pub fn snapshot_time(&self) -> Vec<Duration> {
+        self.iter_time().copied().collect()
     }
 
-    #[test]
-    fn push_two_items_with_same_timestamp() {
-        let mut tv = TimeVec::<()>::builder()
-            .with_limit_nanos(1)
-            .build();
-        
-        tv.push_back_checked(Duration::from_secs(1), ());
-        assert_eq!(tv.len(), 1);
+    #[inline]
+    pub fn max_by_key<K: Ord, F: FnMut(&Item<T>) -> K>(&self, mut f: F) -> Option<&Item<T>> {
+        self.buffer.iter().rev().max_by_key(|item| f(item))
+    }
 
-        tv.push_back_checked(Duration::from_secs(1), ());
-        assert_eq!(tv.len(), 1);
+    #[inline]
+    pub fn min_by_key<K: Ord, F: FnMut(&Item<T>) -> K>(&self, mut f: F) -> Option<&Item<T>> {
+        self.buffer.iter().min_by_key(|item| f(item))
     }
 
-    #[test]
-    fn min_limit() {
-        let mut tv = TimeVec::<()>::builder()
-            .with_limit_nanos(1)
-            .build();
-        
-        tv.push_back(Duration::ZERO, ());
-        assert_eq!(tv.len(), 1);
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
 
-        tv.push_back(Duration::from_nanos(1), ());
-        assert_eq!(tv.len(), 2);
+    /// Sets a new limit and target capacity. The capacity is not shrunk below the
+    /// length before eviction, as evicted items are still held by the returned drain.
+    pub fn reconfigure(&mut self, limit: Duration, capacity: usize) -> Drain<'_, Item<T>> {
+        self.limit = limit;
 
-        tv.push_back(Duration::from_nanos(2), ());
-        assert_eq!(tv.checked_duration(), Some(Duration::from_nanos(1)));
-        assert_eq!(tv.len(), 2);
+        if capacity > self.buffer.capacity() {
+            self.buffer.reserve(capacity - self.buffer.len());
+        } else {
+            self.buffer.shrink_to(capacity);
+        }
+
+        self.clamp_to_limit()
     }
 
-    #[test]
-    fn push_above_the_non_min_limit() {
-        let mut tv = TimeVec::<()>::builder()
-            .with_limit_nanos(3)
-            .build();
-        
-        tv.push_back(Duration::ZERO, ());
-        tv.push_back(Duration::from_nanos(1), ());
-        tv.push_back(Duration::from_nanos(2), ());
-        tv.push_back(Duration::from_nanos(3), ());
-        assert_eq!(tv.len(), 4);
+    pub fn fold_time_weighted<A, F>(&self, init: A, mut f: F) -> A
+    where
+        F: FnMut(A, &T, Duration) -> A,
+    {
+        let mut acc = init;
+        let mut iter = self.buffer.iter().peekable();
+        while let Some(item) = iter.next() {
+            let width = iter.peek().map(|next| next.0 - item.0).unwrap_or_default();
+            acc = f(acc, &item.1, width);
+        }
+        acc
+    }
 
-        tv.push_back(Duration::from_nanos(4), ());
-        assert_eq!(tv.len(), 4);
+    /// Keeps the first item of every `min_spacing` run. The newest item is always kept.
+    pub fn compact(&mut self, min_spacing: Duration) {
+        let len = self.buffer.len();
+        let mut index = 0;
+        let mut last_kept: Option<Duration> = None;
+
+        self.buffer.retain(|item| {
+            index += 1;
+            let keep = index == len || last_kept.map(|t| item.0 - t >= min_spacing).unwrap_or(true);
+            if keep {
+                last_kept = Some(item.0);
+            }
+            keep
+        });
+        self.rebuild_extremes();
+    }
+
+    #[inline]
+    pub fn push_ref(&mut self, timestamp: Duration, item: T) -> Option<&Item<T>> {
+        self.push_back_checked(timestamp, item)?;
+        self.buffer.back()
+    }
+
+    #[inline]
+    pub fn iter_range_idx<'a>(&'a self, range: Range<usize>) -> impl ExactSizeIterator<Item = &'a Item<T>> + 'a {
+        let end = range.end.min(self.buffer.len());
+        self.buffer.range(range.start.min(end)..end)
+    }
+
+    #[inline]
+    pub fn value_at_or_before(&self, at: Duration) -> Option<&T> {
+        let partition_point = self.buffer.partition_point(|i| i.0 <= at);
+        partition_point
+            .checked_sub(1)
+            .map(|index| &self.buffer[index].1)
+    }
+
+    /// Returns the items surrounding `at`. An exact match is returned as both sides.
+    pub fn bracket(&self, at: Duration) -> Option<(&Item<T>, &Item<T>)> {
+        let index = self.buffer.partition_point(|i| i.0 < at);
+        let after = self.buffer.get(index)?;
+
+        if after.0 == at {
+            Some((after, after))
+        } else {
+            index
+                .checked_sub(1)
+                .map(|before| (&self.buffer[before], after))
+        }
+    }
+
+    #[inline]
+    pub fn into_iter_sorted(self) -> impl ExactSizeIterator<Item = Item<T>> {
+        self.buffer.into_iter()
+    }
+
+    pub fn overlap_range(&self, other: &TimeVec<T>) -> Option<(Duration, Duration)> {
+        let (front, back) = self.front_back()?;
+        let (other_front, other_back) = other.front_back()?;
+
+        let start = front.0.max(other_front.0);
+        let end = back.0.min(other_back.0);
+        (start <= end).then_some((start, end))
+    }
+
+    #[inline]
+    pub fn overlaps(&self, other: &TimeVec<T>) -> bool {
+        self.overlap_range(other).is_some()
+    }
+
+    /// Keeps only the items matching `f`. Removal preserves order, so the buffer stays sorted.
+    #[inline]
+    pub fn retain<F: FnMut(&Item<T>) -> bool>(&mut self, f: F) {
+        self.buffer.retain(f);
+        self.rebuild_extremes();
+    }
+
+    /// Keeps only the items whose timestamp matches `f`.
+    #[inline]
+    pub fn retain_time<F: FnMut(Duration) -> bool>(&mut self, mut f: F) {
+        self.retain(|item| f(item.0))
+    }
+
+    /// Merges the parts by timestamp. On equal timestamps the item from the earlier part is kept.
+    pub fn concat<I: IntoIterator<Item = TimeVec<T>>>(parts: I, limit: Duration) -> TimeVec<T> {
+        let merged = MergeParts::new(parts);
+        let mut tv = TimeVec::new(limit, merged.size_hint().1.unwrap_or_default());
+        for (timestamp, item) in merged {
+            tv.push_back_checked(timestamp, item);
+        }
+        tv
+    }
+
+    #[inline]
+    pub fn count_fresh(&self, now: Duration) -> usize {
+        let timestamp = now.saturating_sub(self.limit);
+        self.buffer.len() - self.buffer.partition_point(|i| i.0 < timestamp)
+    }
+
+    #[inline]
+    pub fn truncate_front(&mut self, keep: usize) -> Drain<'_, Item<T>> {
+        let start = keep.min(self.buffer.len());
+        self.untrack_removed(start..self.buffer.len());
+        self.buffer.drain(start..)
+    }
+
+    pub fn mode(&self) -> Option<&T>
+    where
+        T: Eq + Hash,
+    {
+        let mut counts: HashMap<&T, (usize, usize)> = HashMap::new();
+        for (index, value) in self.iter_data().enumerate() {
+            counts.entry(value).or_insert((0, index)).0 += 1;
+        }
+
+        counts
+            .into_iter()
+            .max_by(|(_, a), (_, b)| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+            .map(|(value, _)| value)
+    }
+
+    #[inline]
+    pub fn iter_time_secs_f64<'a>(&'a self) -> impl ExactSizeIterator<Item = f64> + 'a {
+        self.iter_time().map(Duration::as_secs_f64)
+    }
+
+    #[inline]
+    pub fn iter_time_secs_f64_relative<'a>(&'a self) -> impl ExactSizeIterator<Item = f64> + 'a {
+        let front = self.buffer.front().map(|i| i.0).unwrap_or_default();
+        self.iter_time().map(move |t| (*t - front).as_secs_f64())
+    }
+
+    pub fn merge_with<F: Fn(T, T) -> T>(self, other: TimeVec<T>, combine: F) -> TimeVec<T> {
+        let mut tv = TimeVec::new(self.limit, self.buffer.len() + other.buffer.len());
+        let mut left = self.buffer.into_iter().peekable();
+        let mut right = other.buffer.into_iter().peekable();
+
+        loop {
+            let (timestamp, item) = match (left.peek(), right.peek()) {
+                (Some(a), Some(b)) => match a.0.cmp(&b.0) {
+                    Ordering::Less => left.next().unwrap(),
+                    Ordering::Greater => right.next().unwrap(),
+                    Ordering::Equal => {
+                        let (timestamp, a) = left.next().unwrap();
+                        let (_, b) = right.next().unwrap();
+                        (timestamp, combine(a, b))
+                    }
+                },
+                (Some(_), None) => left.next().unwrap(),
+                (None, Some(_)) => right.next().unwrap(),
+                (None, None) => break,
+            };
+            tv.push_back_checked(timestamp, item);
+        }
+
+        tv
+    }
+
+    #[inline]
+    pub fn elapsed_since_front(&self, now: Duration) -> Option<Duration> {
+        self.buffer.front().and_then(|item| now.checked_sub(item.0))
+    }
+
+    pub fn interval_stats(&self) -> Option<IntervalStats> {
+        let (min, max) = self.buffer
+            .iter()
+            .zip(self.buffer.iter().skip(1))
+            .map(|(a, b)| b.0 - a.0)
+            .fold(None, |acc: Option<(Duration, Duration)>, gap| match acc {
+                Some((min, max)) => Some((min.min(gap), max.max(gap))),
+                None => Some((gap, gap)),
+            })?;
+        let mean = duration_from_nanos(self.duration().as_nanos() / (self.buffer.len() - 1) as u128);
+
+        Some(IntervalStats { min, max, mean, jitter: max - min })
+    }
+
+    #[inline]
+    pub fn covered_duration_in(&self, start: Duration, end: Duration) -> Duration {
+        let range = self.index_range(start..=end);
+        if range.len() < 2 {
+            return Duration::ZERO;
+        }
+        self.buffer[range.end - 1].0 - self.buffer[range.start].0
+    }
+
+    /// Estimated size of the stored items in bytes. Heap data owned by `T` is not counted.
+    #[inline]
+    pub fn memory_bytes(&self) -> usize {
+        self.buffer.len() * size_of::<Item<T>>()
+    }
+
+    /// Evicts the oldest items until the `memory_bytes` estimate fits into `budget`.
+    pub fn prune_to_bytes(&mut self, budget: usize) -> Drain<'_, Item<T>> {
+        let keep = budget
+            .checked_div(size_of::<Item<T>>())
+            .unwrap_or(usize::MAX)
+            .min(self.buffer.len());
+        self.untrack_removed(0..self.buffer.len() - keep);
+        self.buffer.drain(0..self.buffer.len() - keep)
+    }
+
+    #[inline]
+    pub fn iter_mut_with_time<'a>(&'a mut self) -> impl ExactSizeIterator<Item = (Duration, &'a mut T)> + 'a {
+        self.invalidate_extremes();
+        self.buffer.iter_mut().map(|item| (item.0, &mut item.1))
+    }
+
+    pub fn push_report(&mut self, timestamp: Duration, item: T) -> PushReport<T> {
+        let capacity = self.buffer.capacity();
+        let evicted = self.push_back_checked(timestamp, item).map(Iterator::collect);
+
+        PushReport {
+            accepted: evicted.is_some(),
+            evicted: evicted.unwrap_or_default(),
+            reallocated: capacity != self.buffer.capacity(),
+        }
+    }
+
+    /// Reserves space for exactly `additional` more items, so `capacity()` becomes
+    /// at least `len() + additional` without the amortized slack of growth.
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.buffer.reserve_exact(additional)
+    }
+
+    /// Number of items evicted by the time limit since creation or the last `reset_stats`.
+    #[inline]
+    pub fn total_evicted(&self) -> u64 {
+        self.evicted
+    }
+
+    #[inline]
+    pub fn reset_stats(&mut self) {
+        self.evicted = 0;
+    }
+
+    #[inline]
+    pub fn iter_until<'a>(&'a self, before: Duration) -> impl ExactSizeIterator<Item = &'a Item<T>> + 'a {
+        let partition_point = self.buffer.partition_point(|i| i.0 < before);
+        self.buffer.range(..partition_point)
+    }
+
+    #[inline]
+    pub fn window_indices<'a>(&'a self, window: Duration) -> impl ExactSizeIterator<Item = (usize, usize)> + 'a {
+        self.buffer.iter().enumerate().map(move |(index, item)| {
+            let start = item.0.saturating_sub(window);
+            (self.buffer.partition_point(|i| i.0 < start), index + 1)
+        })
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_jsonl(&self) -> serde_json::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let mut lines = String::new();
+        for item in self.buffer.iter() {
+            lines.push_str(&format!("{{\"t_nanos\":{},\"value\":", item.0.as_nanos()));
+            lines.push_str(&serde_json::to_string(&item.1)?);
+            lines.push_str("}\n");
+        }
+        Ok(lines)
+    }
+
+    fn merge_sorted<I: Iterator<Item = Item<T>>>(&mut self, items: I) {
+        let capacity = self.buffer.capacity().max(self.buffer.len() + items.size_hint().0);
+        let buffer = mem::replace(&mut self.buffer, VecDeque::with_capacity(capacity));
+
+        let mut left = buffer.into_iter().peekable();
+        let mut right = items.peekable();
+        loop {
+            let next = match (left.peek(), right.peek()) {
+                (Some(a), Some(b)) if b.0 < a.0 => right.next(),
+                (Some(_), _) => left.next(),
+                (None, _) => right.next(),
+            };
+            match next {
+                Some((timestamp, item)) if self.timestamp_is_ok(timestamp) => {
+                    self.buffer.push_back((timestamp, item));
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        self.rebuild_extremes();
+    }
+
+    /// Inserts items at their sorted positions and evicts once. Items with a timestamp
+    /// that is already present are dropped, keeping the existing item.
+    pub fn insert_many<I: IntoIterator<Item = Item<T>>>(&mut self, items: I) -> Drain<'_, Item<T>> {
+        let mut items: Vec<Item<T>> = items.into_iter().collect();
+        items.sort_by_key(|item| item.0);
+        if self.rejects_all() {
+            return self.hand_back(items);
+        }
+
+        self.merge_sorted(items.into_iter());
+        self.evict_for_back()
+    }
+
+    pub fn trim_ends(&mut self, from_front: Duration, from_back: Duration) -> (Vec<Item<T>>, Vec<Item<T>>) {
+        let (front, back) = match self.front_back() {
+            Some((front, back)) => (front.0, back.0),
+            None => return (Vec::new(), Vec::new()),
+        };
+
+        let start = front.saturating_add(from_front);
+        let head = self.buffer.partition_point(|i| i.0 < start);
+        self.untrack_removed(0..head);
+        let head: Vec<Item<T>> = self.buffer.drain(..head).collect();
+
+        let tail = match back.checked_sub(from_back) {
+            Some(end) => self.buffer.partition_point(|i| i.0 <= end),
+            None => 0,
+        };
+        self.untrack_removed(tail..self.buffer.len());
+        let tail: Vec<Item<T>> = self.buffer.drain(tail..).collect();
+
+        (head, tail)
+    }
+
+    /// Rounds timestamps to the nearest multiple of `resolution`. When several items
+    /// round to the same timestamp, the newest one is kept.
+    pub fn quantize_time(&mut self, resolution: Duration) {
+        assert!(!resolution.is_zero(), "Resolution must be non-zero.");
+
+        let step = resolution.as_nanos();
+        let capacity = self.buffer.capacity();
+        let buffer = mem::replace(&mut self.buffer, VecDeque::with_capacity(capacity));
+        for (timestamp, item) in buffer {
+            let nanos = timestamp.as_nanos();
+            let down = nanos - nanos % step;
+            let rounded = match down.checked_add(step) {
+                Some(up) if nanos - down >= up - nanos && up <= Duration::MAX.as_nanos() => up,
+                _ => down,
+            };
+            let timestamp = duration_from_nanos(rounded);
+
+            match self.buffer.back_mut() {
+                Some(back) if back.0 == timestamp => back.1 = item,
+                _ => self.buffer.push_back((timestamp, item)),
+            }
+        }
+        self.rebuild_extremes();
+    }
+
+    #[inline]
+    pub fn iter_rebased<'a>(&'a self) -> impl ExactSizeIterator<Item = (Duration, &'a T)> + 'a {
+        let front = self.buffer.front().map(|i| i.0).unwrap_or_default();
+        self.buffer.iter().map(move |item| (item.0 - front, &item.1))
+    }
+
+    /// Number of items a push at `timestamp` would evict, or `None` when
+    /// `push_back_checked` would reject it.
+    #[inline]
+    pub fn would_evict(&self, timestamp: Duration) -> Option<usize> {
+        (self.timestamp_is_ok(timestamp) && !self.rejects_all()).then(|| {
+            self.push_eviction_count(timestamp)
+        })
+    }
+
+    #[inline]
+    fn with_buffer(&self, buffer: VecDeque<Item<T>>) -> TimeVec<T> {
+        TimeVec {
+            limit: self.limit,
+            sink: None,
+            policy: self.policy.clone(),
+            evicted: 0,
+            strict: self.strict,
+            extremes: self.extremes.as_ref().map(|e| Extremes::from_buffer(e.value, &buffer)),
+            buffer,
+        }
+    }
+
+    pub fn split_by_gap(mut self, gap: Duration) -> Vec<TimeVec<T>> {
+        let mut parts = Vec::new();
+        let mut current = VecDeque::new();
+
+        for item in mem::take(&mut self.buffer) {
+            if current.back().is_some_and(|back: &Item<T>| item.0 - back.0 > gap) {
+                parts.push(self.with_buffer(mem::take(&mut current)));
+            }
+            current.push_back(item);
+        }
+        if !current.is_empty() {
+            parts.push(self.with_buffer(current));
+        }
+
+        parts
+    }
+
+    #[inline]
+    pub fn map_in_place<F: FnMut(&mut T)>(&mut self, mut f: F) {
+        self.buffer.iter_mut().for_each(|item| f(&mut item.1));
+        self.rebuild_extremes();
+    }
+
+    /// Returns the item closest to `timestamp`, preferring the older one on ties.
+    pub fn nearest(&self, timestamp: Duration) -> Option<&Item<T>> {
+        let index = self.buffer.partition_point(|i| i.0 < timestamp);
+        let before = index.checked_sub(1).and_then(|i| self.buffer.get(i));
+        let after = self.buffer.get(index);
+
+        match (before, after) {
+            (Some(a), Some(b)) if b.0 - timestamp < timestamp - a.0 => Some(b),
+            (Some(a), _) => Some(a),
+            (None, b) => b,
+        }
+    }
+
+    #[inline]
+    pub fn nearest_within(&self, timestamp: Duration, tol: Duration) -> Option<&Item<T>> {
+        self.nearest(timestamp)
+            .filter(|item| item.0.abs_diff(timestamp) <= tol)
+    }
+
+    /// Removes and returns the items older than `now - limit`, bypassing the sink.
+    pub fn drain_stale(&mut self, now: Duration) -> Vec<Item<T>> {
+        let timestamp = now.saturating_sub(self.limit);
+        let partition_point = self.buffer.partition_point(|i| i.0 < timestamp);
+        self.untrack_removed(0..partition_point);
+        self.buffer.drain(..partition_point).collect()
+    }
+
+    #[inline]
+    pub fn capacity_utilization(&self) -> f64 {
+        match self.buffer.capacity() {
+            0 => 0.0,
+            capacity => self.buffer.len() as f64 / capacity as f64,
+        }
+    }
+
+    /// Pushes and evicts by both the back and an external `now`. Returns `None` without
+    /// evicting when the item is rejected, like `push_back_checked`.
+    pub fn push_with_now(&mut self, timestamp: Duration, item: T, now: Duration) -> Option<Drain<'_, Item<T>>> {
+        if !self.timestamp_is_ok(timestamp) || self.rejects_all() {
+            return None;
+        }
+
+        let cutoff = now.saturating_sub(self.limit);
+        let by_clock = self.buffer.partition_point(|i| i.0 < cutoff);
+        let count = by_clock.max(self.push_eviction_count(timestamp));
+        self.buffer.push_back((timestamp, item));
+        self.track_pushed(self.buffer.len() - 1);
+
+        Some(self.evict_front(count))
+    }
+
+    #[inline]
+    pub fn first_index_after(&self, t: Duration) -> Option<usize> {
+        let index = self.buffer.partition_point(|i| i.0 <= t);
+        (index < self.buffer.len()).then_some(index)
+    }
+
+    #[inline]
+    pub fn last_index_before(&self, t: Duration) -> Option<usize> {
+        self.buffer.partition_point(|i| i.0 < t).checked_sub(1)
+    }
+
+    #[inline]
+    pub fn drain_keep_newest(&mut self, n: usize) -> Vec<Item<T>> {
+        let count = self.buffer.len().saturating_sub(n);
+        self.untrack_removed(0..count);
+        self.buffer.drain(..count).collect()
+    }
+
+    #[inline]
+    pub fn has_coverage(&self, window: Duration) -> bool {
+        self.duration() >= window
+    }
+
+    /// Adjacent pairs as `(dt_secs, older, newer)`.
+    pub fn iter_pairs_dt<'a>(&'a self) -> impl Iterator<Item = (f64, &'a T, &'a T)> + 'a {
+        self.buffer
+            .iter()
+            .zip(self.buffer.iter().skip(1))
+            .map(|(older, newer)| ((newer.0 - older.0).as_secs_f64(), &older.1, &newer.1))
+    }
+
+    /// Items within `limit` of an external `now`, without evicting anything.
+    pub fn fresh_iter<'a>(&'a self, now: Duration) -> impl ExactSizeIterator<Item = &'a Item<T>> + 'a {
+        let timestamp = now.saturating_sub(self.limit);
+        let partition_point = self.buffer.partition_point(|i| i.0 < timestamp);
+        self.buffer.range(partition_point..)
+    }
+
+    /// Streams an already sorted iterator into the buffer and evicts once. Unsorted input is
+    /// not reordered: items that are not newer than the previously kept one are dropped,
+    /// as are exact timestamp ties with existing items.
+    pub fn merge_iter<I: IntoIterator<Item = Item<T>>>(&mut self, iter: I) -> Drain<'_, Item<T>> {
+        if self.rejects_all() {
+            return self.hand_back(iter);
+        }
+        self.merge_sorted(iter.into_iter());
+        self.evict_for_back()
+    }
+
+    /// Consumes the vector into its limit and raw buffer. The sink and policy are dropped.
+    #[inline]
+    pub fn into_parts(self) -> (Duration, VecDeque<Item<T>>) {
+        (self.limit, self.buffer)
+    }
+
+    /// Rebuilds from raw parts without copying. The caller must ensure timestamps are
+    /// strictly increasing; this is only checked in debug builds.
+    #[inline]
+    pub fn from_parts(limit: Duration, buffer: VecDeque<Item<T>>) -> Self {
+        let tv = Self { limit, buffer, sink: None, policy: None, evicted: 0, strict: false, extremes: None };
+        debug_assert!(tv.is_monotonic(), "timestamps must be strictly increasing");
+        tv
+    }
+
+    /// Sample counts per `interval` bucket aligned to the front, including empty buckets.
+    pub fn counts_per_interval(&self, interval: Duration) -> Vec<(Duration, usize)> {
+        assert!(!interval.is_zero(), "Interval must be non-zero.");
+
+        let mut result: Vec<(Duration, usize)> = Vec::new();
+        let front = match self.buffer.front() {
+            Some(front) => front.0,
+            None => return result,
+        };
+
+        for timestamp in self.iter_time() {
+            let start = bucket_start(front, *timestamp, interval);
+            loop {
+                match result.last_mut() {
+                    Some((bucket, count)) if *bucket == start => {
+                        *count += 1;
+                        break;
+                    }
+                    Some((bucket, _)) => {
+                        let next = *bucket + interval;
+                        result.push((next, 0));
+                    }
+                    None => result.push((start, 0)),
+                }
+            }
+        }
+        result
+    }
+
+    /// Pushes unless the buffer would still hold more than `max_len` items after eviction
+    /// by the policy or time limit, in which case nothing changes and the item is handed back.
+    /// Panics on out of order timestamps like `push_back`.
+    pub fn push_bounded(
+        &mut self,
+        timestamp: Duration,
+        item: T,
+        max_len: usize,
+    ) -> Result<Drain<'_, Item<T>>, CapacityError<T>> {
+        assert!(self.timestamp_is_ok(timestamp), "Timestamp is older then previous.");
+        if self.rejects_all() {
+            return Err(CapacityError((timestamp, item)));
+        }
+
+        let count = self.push_eviction_count(timestamp);
+        if self.buffer.len() - count >= max_len {
+            return Err(CapacityError((timestamp, item)));
+        }
+        self.buffer.push_back((timestamp, item));
+        self.track_pushed(self.buffer.len() - 1);
+        Ok(self.evict_front(count))
+    }
+
+    /// Splits into `(matching, rest)`, both keeping time order and the limit.
+    pub fn partition_into<F: FnMut(&Item<T>) -> bool>(mut self, f: F) -> (TimeVec<T>, TimeVec<T>) {
+        let (matching, rest): (VecDeque<_>, VecDeque<_>) = mem::take(&mut self.buffer)
+            .into_iter()
+            .partition(f);
+        (self.with_buffer(matching), self.with_buffer(rest))
+    }
+
+    /// Swaps the value at exactly `timestamp`, returning the old one.
+    pub fn replace_at(&mut self, timestamp: Duration, value: T) -> Option<T> {
+        let index = self.buffer.binary_search_by_key(&timestamp, |i| i.0).ok()?;
+        self.invalidate_extremes();
+        Some(mem::replace(&mut self.buffer[index].1, value))
+    }
+
+    /// Moves the items out into a new `TimeVec`, leaving `self` empty with the same
+    /// configuration. The sink stays with `self`.
+    pub fn take(&mut self) -> TimeVec<T> {
+        let buffer = mem::take(&mut self.buffer);
+        let taken = self.with_buffer(buffer);
+        self.rebuild_extremes();
+        taken
+    }
+
+    /// Items whose value differs from the previous one, always starting with the first.
+    pub fn iter_changes<'a>(&'a self) -> impl Iterator<Item = &'a Item<T>> + 'a
+    where
+        T: PartialEq,
+    {
+        let mut previous: Option<&'a T> = None;
+        self.buffer.iter().filter(move |item| {
+            let changed = previous != Some(&item.1);
+            previous = Some(&item.1);
+            changed
+        })
+    }
+
+    /// Reserves enough for a full `limit` at the mean observed interval. No-op with fewer
+    /// than two items, and when the estimate cannot be allocated.
+    pub fn reserve_for_observed_rate(&mut self) {
+        let mean = match self.interval_stats() {
+            Some(stats) => stats.mean,
+            None => return,
+        };
+        let expected = Self::capacity_for(self.limit, 1.0 / mean.as_secs_f64()).saturating_add(1);
+        let _ = self.buffer.try_reserve(expected.saturating_sub(self.buffer.len()));
+    }
+
+    /// Borrows the items before and from `at`. Always allocates two `Vec`s since the ring
+    /// may not be contiguous; `at` is clamped to `len()`.
+    pub fn split_view(&self, at: usize) -> (Vec<&Item<T>>, Vec<&Item<T>>) {
+        let at = at.min(self.buffer.len());
+        (self.buffer.range(..at).collect(), self.buffer.range(at..).collect())
+    }
+
+    /// Items within each sample's trailing `window`, allocating one `Vec` per sample.
+    pub fn time_windows<'a>(&'a self, window: Duration) -> impl ExactSizeIterator<Item = Vec<&'a Item<T>>> + 'a {
+        self.window_indices(window)
+            .map(move |(start, end)| self.buffer.range(start..end).collect())
+    }
+
+    /// Removes the oldest `ceil(len * fraction)` items, with `fraction` clamped to `[0, 1]`.
+    pub fn trim_fraction(&mut self, fraction: f64) -> Vec<Item<T>> {
+        let count = (self.buffer.len() as f64 * fraction.clamp(0.0, 1.0)).ceil() as usize;
+        self.pop_front_n(count)
+    }
+
+    /// Pops up to `n` of the oldest items, oldest first.
+    #[inline]
+    pub fn pop_front_n(&mut self, n: usize) -> Vec<Item<T>> {
+        let count = n.min(self.buffer.len());
+        self.untrack_removed(0..count);
+        self.buffer.drain(..count).collect()
+    }
+
+    /// Pops up to `n` of the newest items, oldest first.
+    #[inline]
+    pub fn pop_back_n(&mut self, n: usize) -> Vec<Item<T>> {
+        let start = self.buffer.len().saturating_sub(n);
+        self.untrack_removed(start..self.buffer.len());
+        self.buffer.drain(start..).collect()
+    }
+
+    /// Value at index `round(f * (len - 1))` with `f` clamped to `[0, 1]`.
+    pub fn value_at_fraction(&self, f: f64) -> Option<&T> {
+        let last = self.buffer.len().checked_sub(1)?;
+        let index = (f.clamp(0.0, 1.0) * last as f64).round() as usize;
+        self.buffer.get(index).map(|item| &item.1)
+    }
+}
+
+impl<T: Clone> TimeVec<T> {
+    pub fn resample_hold(&self, interval: Duration) -> Vec<(Duration, T)> {
+        assert!(!interval.is_zero(), "Interval must be non-zero.");
+
+        let mut result = Vec::new();
+        let (front, back) = match (self.buffer.front(), self.buffer.back()) {
+            (Some(front), Some(back)) => (front.0, back.0),
+            _ => return result,
+        };
+
+        let mut index = 0;
+        let mut timestamp = front;
+        loop {
+            while index + 1 < self.buffer.len() && self.buffer[index + 1].0 <= timestamp {
+                index += 1;
+            }
+            result.push((timestamp, self.buffer[index].1.clone()));
+
+            match timestamp.checked_add(interval) {
+                Some(next) if next <= back => timestamp = next,
+                _ => break,
+            }
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn snapshot_data(&self) -> Vec<T> {
+        self.iter_data().cloned().collect()
+    }
+
+    pub fn sorted_by_value_by<F>(&self, mut compare: F) -> Vec<Item<T>>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut items: Vec<Item<T>> = self.buffer.iter().cloned().collect();
+        items.sort_by(|a, b| compare(&a.1, &b.1));
+        items
+    }
+
+    #[inline]
+    pub fn sorted_by_value(&self) -> Vec<Item<T>>
+    where
+        T: Ord,
+    {
+        self.sorted_by_value_by(T::cmp)
+    }
+
+    pub fn compact_clone(&self) -> TimeVec<T> {
+        let mut buffer = VecDeque::with_capacity(self.buffer.len());
+        buffer.extend(self.buffer.iter().cloned());
+        TimeVec {
+            limit: self.limit,
+            buffer,
+            sink: None,
+            policy: self.policy.clone(),
+            evicted: self.evicted,
+            strict: self.strict,
+            extremes: self.extremes.clone(),
+        }
+    }
+
+    pub fn extend_from(&mut self, other: &TimeVec<T>) -> Drain<'_, Item<T>> {
+        let start = match self.buffer.back() {
+            Some(back) => other.buffer.partition_point(|i| i.0 <= back.0),
+            None => 0,
+        };
+        if self.rejects_all() {
+            return self.hand_back(other.buffer.range(start..).cloned());
+        }
+        let len = self.buffer.len();
+        self.buffer.extend(other.buffer.range(start..).cloned());
+        self.track_pushed(len);
+        self.evict_for_back()
+    }
+
+    /// Runs of equal values as `(start, end, value)`; `end` is the next run's start.
+    pub fn rle(&self) -> Vec<(Duration, Duration, T)>
+    where
+        T: PartialEq,
+    {
+        let mut runs: Vec<(Duration, Duration, T)> = Vec::new();
+        for (timestamp, value) in self.iter() {
+            match runs.last_mut() {
+                Some(run) if run.2 == *value => run.1 = *timestamp,
+                Some(run) => {
+                    run.1 = *timestamp;
+                    runs.push((*timestamp, *timestamp, value.clone()));
+                }
+                None => runs.push((*timestamp, *timestamp, value.clone())),
+            }
+        }
+        runs
+    }
+
+    /// Borrows the items when the ring is contiguous, otherwise copies them.
+    pub fn contiguous_or_owned(&self) -> Cow<'_, [Item<T>]> {
+        match self.buffer.as_slices() {
+            (front, []) => Cow::Borrowed(front),
+            (front, back) => Cow::Owned([front, back].concat()),
+        }
+    }
+}
+
+impl TimeVec<f64> {
+    #[inline]
+    pub fn sum(&self) -> f64 {
+        self.iter_data().sum()
+    }
+
+    pub fn cumulative_sum(&self) -> Vec<(Duration, f64)> {
+        self.buffer
+            .iter()
+            .scan(0.0, |total, item| {
+                *total += item.1;
+                Some((item.0, *total))
+            })
+            .collect()
+    }
+
+    pub fn recency_weighted_mean(&self, half_life: Duration) -> Option<f64> {
+        let back = self.buffer.back()?.0;
+        let half_life = half_life.as_secs_f64();
+
+        let (total, weights) = self.buffer.iter().fold((0.0, 0.0), |(total, weights), item| {
+            let age = (back - item.0).as_secs_f64();
+            let weight = if age == 0.0 { 1.0 } else { 0.5f64.powf(age / half_life) };
+            (total + weight * item.1, weights + weight)
+        });
+
+        (weights > 0.0).then(|| total / weights)
+    }
+
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray(&self) -> (ndarray::Array1<f64>, ndarray::Array1<f64>) {
+        let time = self.iter_time().map(Duration::as_secs_f64).collect();
+        let data = self.iter_data().copied().collect();
+        (time, data)
+    }
+
+    #[inline]
+    pub fn downsample_mean(&self, interval: Duration) -> Vec<(Duration, f64)> {
+        bucket_means(self.buffer.iter().copied(), interval)
+    }
+
+    /// Returns items strictly greater than both neighbors. Endpoints are never included.
+    pub fn local_maxima(&self) -> Vec<&Item<f64>> {
+        self.local_extrema(|value, neighbor| value > neighbor)
+    }
+
+    /// Returns items strictly less than both neighbors. Endpoints are never included.
+    pub fn local_minima(&self) -> Vec<&Item<f64>> {
+        self.local_extrema(|value, neighbor| value < neighbor)
+    }
+
+    fn local_extrema<F: Fn(f64, f64) -> bool>(&self, beats: F) -> Vec<&Item<f64>> {
+        (1..self.buffer.len().saturating_sub(1))
+            .filter(|&index| {
+                let value = self.buffer[index].1;
+                beats(value, self.buffer[index - 1].1) && beats(value, self.buffer[index + 1].1)
+            })
+            .map(|index| &self.buffer[index])
+            .collect()
+    }
+
+    #[inline]
+    pub fn interpolate(&self, at: Duration) -> Option<f64> {
+        self.bracket(at).map(|(before, after)| lerp(before, after, at))
+    }
+
+    /// Interpolates at every query time in one pass. `queries` must be sorted ascending.
+    pub fn interpolate_many(&self, queries: &[Duration]) -> Vec<Option<f64>> {
+        let mut index = 0;
+        queries
+            .iter()
+            .map(|&at| {
+                while index < self.buffer.len() && self.buffer[index].0 < at {
+                    index += 1;
+                }
+                let after = self.buffer.get(index)?;
+                if after.0 == at {
+                    Some(after.1)
+                } else {
+                    index
+                        .checked_sub(1)
+                        .map(|before| lerp(&self.buffer[before], after, at))
+                }
+            })
+            .collect()
+    }
+
+    #[inline]
+    pub fn mean(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.sum() / self.len() as f64)
+    }
+
+    /// Sample variance, using `n - 1` in the denominator.
+    pub fn variance(&self) -> Option<f64> {
+        if self.len() < 2 {
+            return None;
+        }
+        let mean = self.mean()?;
+        let squares: f64 = self.iter_data().map(|v| (v - mean).powi(2)).sum();
+        Some(squares / (self.len() - 1) as f64)
+    }
+
+    /// Sample standard deviation, using `n - 1` in the denominator.
+    #[inline]
+    pub fn stddev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    #[inline]
+    pub fn coefficient_of_variation(&self) -> Option<f64> {
+        let mean = self.mean().filter(|mean| *mean != 0.0)?;
+        self.stddev().map(|stddev| stddev / mean)
+    }
+
+    pub fn first_crossing(&self, threshold: f64, rising: bool) -> Option<Duration> {
+        self.buffer
+            .iter()
+            .zip(self.buffer.iter().skip(1))
+            .find(|(a, b)| {
+                if rising {
+                    a.1 < threshold && b.1 >= threshold
+                } else {
+                    a.1 > threshold && b.1 <= threshold
+                }
+            })
+            .map(|(a, b)| {
+                let fraction = (threshold - a.1) / (b.1 - a.1);
+                if fraction.is_finite() {
+                    a.0 + (b.0 - a.0).mul_f64(fraction.clamp(0.0, 1.0))
+                } else {
+                    b.0
+                }
+            })
+    }
+
+    /// Clamps every value into `[min, max]`. NaN values are left unchanged.
+    /// Panics if `min > max`, like `f64::clamp`.
+    pub fn clamp_values(&mut self, min: f64, max: f64) {
+        for item in self.buffer.iter_mut() {
+            item.1 = item.1.clamp(min, max);
+        }
+        self.rebuild_extremes();
+    }
+
+    pub fn replace_nan(&mut self, with: f64) -> usize {
+        let mut count = 0;
+        for item in self.buffer.iter_mut().filter(|item| item.1.is_nan()) {
+            item.1 = with;
+            count += 1;
+        }
+        self.rebuild_extremes();
+        count
+    }
+
+    pub fn drop_nan(&mut self) -> usize {
+        let len = self.buffer.len();
+        self.buffer.retain(|item| !item.1.is_nan());
+        self.rebuild_extremes();
+        len - self.buffer.len()
+    }
+
+    pub fn rolling_max(&self, window: Duration) -> Vec<(Duration, f64)> {
+        self.rolling_extreme(window, |candidate, value| candidate <= value)
+    }
+
+    pub fn rolling_min(&self, window: Duration) -> Vec<(Duration, f64)> {
+        self.rolling_extreme(window, |candidate, value| candidate >= value)
+    }
+
+    fn rolling_extreme<F>(&self, window: Duration, dominated: F) -> Vec<(Duration, f64)>
+    where
+        F: Fn(f64, f64) -> bool,
+    {
+        let mut indices: VecDeque<usize> = VecDeque::new();
+        let mut result = Vec::with_capacity(self.buffer.len());
+
+        for (index, item) in self.buffer.iter().enumerate() {
+            while indices.back().is_some_and(|&i| dominated(self.buffer[i].1, item.1)) {
+                indices.pop_back();
+            }
+            indices.push_back(index);
+
+            let start = item.0.saturating_sub(window);
+            while indices.front().is_some_and(|&i| self.buffer[i].0 < start) {
+                indices.pop_front();
+            }
+            result.push((item.0, self.buffer[indices[0]].1));
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn filter_value_band(&self, low: f64, high: f64) -> Vec<&Item<f64>> {
+        self.buffer
+            .iter()
+            .filter(|item| low <= item.1 && item.1 <= high)
+            .collect()
+    }
+
+    /// Scales values into `[0, 1]` by the window min and max. A constant series becomes all `0.0`.
+    pub fn normalize_values(&mut self) {
+        let (min, max) = match self.value_range() {
+            Some(range) => range,
+            None => return,
+        };
+        let span = max - min;
+
+        for item in self.buffer.iter_mut() {
+            item.1 = if span > 0.0 { (item.1 - min) / span } else { 0.0 };
+        }
+        self.rebuild_extremes();
+    }
+
+    /// Returns `self - other` per item, or `None` unless both have the same timestamps.
+    pub fn diff_against(&self, other: &TimeVec<f64>) -> Option<Vec<(Duration, f64)>> {
+        if self.len() != other.len() {
+            return None;
+        }
+        self.buffer
+            .iter()
+            .zip(other.buffer.iter())
+            .map(|(a, b)| (a.0 == b.0).then_some((a.0, a.1 - b.1)))
+            .collect()
+    }
+
+    #[inline]
+    pub fn median(&self) -> Option<f64> {
+        median_of(self.iter_data().copied().collect())
+    }
+
+    pub fn mad(&self) -> Option<f64> {
+        let median = self.median()?;
+        median_of(self.iter_data().map(|v| (v - median).abs()).collect())
+    }
+
+    #[inline]
+    pub fn push_if_changed(&mut self, timestamp: Duration, value: f64, epsilon: f64) -> Option<Drain<'_, Item<f64>>> {
+        self.push_if(timestamp, value, |back| {
+            // A NaN on either side is incomparable and counts as a change.
+            back.map(|i| {
+                (value - i.1).abs().partial_cmp(&epsilon).is_none_or(Ordering::is_gt)
+            })
+            .unwrap_or(true)
+        })
+    }
+
+    pub fn resample_onto<U>(&self, other: &TimeVec<U>) -> Vec<(Duration, Option<f64>)> {
+        let times = other.snapshot_time();
+        let values = self.interpolate_many(&times);
+        times.into_iter().zip(values).collect()
+    }
+
+    /// Pearson correlation of values paired by index. Lengths must match.
+    pub fn correlation(&self, other: &TimeVec<f64>) -> Option<f64> {
+        if self.len() != other.len() {
+            return None;
+        }
+        pearson(self.iter_data().copied().zip(other.iter_data().copied()))
+    }
+
+    pub fn approx_eq_values(&self, other: &TimeVec<f64>, abs_tol: f64) -> bool {
+        self.len() == other.len()
+            && self.buffer
+                .iter()
+                .zip(other.buffer.iter())
+                .all(|(a, b)| a.0 == b.0 && (a.1 - b.1).abs() <= abs_tol)
+    }
+
+    pub fn value_range(&self) -> Option<(f64, f64)> {
+        let mut iter = self.iter_data();
+        let first = *iter.next()?;
+        Some(iter.fold((first, first), |(min, max), v| (min.min(*v), max.max(*v))))
+    }
+
+    #[inline]
+    pub fn sum_in_range(&self, start: Duration, end: Duration) -> f64 {
+        self.buffer.range(self.index_range(start..end)).map(|i| i.1).sum()
+    }
+
+    pub fn to_step_points(&self) -> Vec<(Duration, f64)> {
+        let mut points = Vec::with_capacity(self.buffer.len() * 2);
+        for item in self.buffer.iter() {
+            if let Some(&(_, previous)) = points.last() {
+                points.push((item.0, previous));
+            }
+            points.push(*item);
+        }
+        points
+    }
+
+    /// Averages the step signal over consecutive intervals starting at the front.
+    /// The last interval is cut at the back and averaged over its covered width.
+    pub fn resample_area(&self, interval: Duration) -> Vec<(Duration, f64)> {
+        assert!(!interval.is_zero(), "Interval must be non-zero.");
+
+        let mut result = Vec::new();
+        let (front, back) = match self.front_back() {
+            Some((front, back)) => (front.0, back.0),
+            None => return result,
+        };
+
+        let len = self.buffer.len();
+        let mut index = 0;
+        let mut start = front;
+        while start < back {
+            let end = start.saturating_add(interval).min(back);
+            while index + 1 < len && self.buffer[index + 1].0 <= start {
+                index += 1;
+            }
+
+            let mut area = 0.0;
+            let mut i = index;
+            while i + 1 < len && self.buffer[i].0 < end {
+                let width = self.buffer[i + 1].0.min(end) - self.buffer[i].0.max(start);
+                area += self.buffer[i].1 * width.as_secs_f64();
+                i += 1;
+            }
+
+            result.push((start, area / (end - start).as_secs_f64()));
+            start = end;
+        }
+
+        result
+    }
+
+    /// Correlates each value with the signal interpolated `lag` later.
+    pub fn autocorrelation(&self, lag: Duration) -> Option<f64> {
+        let back = self.buffer.back()?.0;
+        let queries: Vec<Duration> = self
+            .iter_time()
+            .filter_map(|t| t.checked_add(lag))
+            .take_while(|t| *t <= back)
+            .collect();
+        let shifted = self.interpolate_many(&queries);
+
+        pearson(
+            self.iter_data()
+                .zip(shifted)
+                .filter_map(|(value, other)| other.map(|other| (*value, other))),
+        )
+    }
+
+    /// Pairs each of `self`'s samples with `other` interpolated at the same time. Samples
+    /// outside `other`'s range are skipped.
+    pub fn zip_interpolated(&self, other: &TimeVec<f64>) -> Vec<(Duration, f64, f64)> {
+        let queries: Vec<Duration> = self.iter_time().copied().collect();
+        self.iter()
+            .zip(other.interpolate_many(&queries))
+            .filter_map(|(&(timestamp, value), other)| other.map(|other| (timestamp, value, other)))
+            .collect()
+    }
+
+    /// Running minimum, O(1) with `track_extremes`. Without tracking, or after removing
+    /// from the back or middle or `iter_mut_with_time` until the next push, a full scan.
+    pub fn current_min(&self) -> Option<f64> {
+        match self.extremes.as_ref() {
+            Some(extremes) if !extremes.stale => extremes
+                .min
+                .front()
+                .map(|m| m.1)
+                .or_else(|| self.buffer.front().map(|_| f64::NAN)),
+            _ => self.value_range().map(|(min, _)| min),
+        }
+    }
+
+    /// Running maximum; see `current_min`.
+    pub fn current_max(&self) -> Option<f64> {
+        match self.extremes.as_ref() {
+            Some(extremes) if !extremes.stale => extremes
+                .max
+                .front()
+                .map(|m| m.1)
+                .or_else(|| self.buffer.front().map(|_| f64::NAN)),
+            _ => self.value_range().map(|(_, max)| max),
+        }
+    }
+
+    /// One block character per value, scaled between the window min and max.
+    pub fn sparkline(&self) -> String {
+        const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+        let (min, max) = match self.value_range() {
+            Some(range) => range,
+            None => return String::new(),
+        };
+        let span = max - min;
+        self.iter_data()
+            .map(|value| {
+                let level = if span > 0.0 {
+                    ((value - min) / span * (BLOCKS.len() - 1) as f64).round() as usize
+                } else {
+                    BLOCKS.len() / 2
+                };
+                BLOCKS[level.min(BLOCKS.len() - 1)]
+            })
+            .collect()
+    }
+
+    /// Highest value after linear decay of `decay_per_sec` from its time to the back.
+    pub fn peak_hold(&self, decay_per_sec: f64) -> Option<f64> {
+        let back = self.buffer.back()?.0;
+        self.iter()
+            .map(|(timestamp, value)| value - decay_per_sec * (back - *timestamp).as_secs_f64())
+            .reduce(f64::max)
+    }
+
+    /// Per-interval means of the parts merged by timestamp, without building the merged
+    /// buffer. Matches `concat` followed by `downsample_mean` when no items are evicted.
+    pub fn merge_downsampled<I: IntoIterator<Item = TimeVec<f64>>>(
+        parts: I,
+        interval: Duration,
+    ) -> Vec<(Duration, f64)> {
+        bucket_means(MergeParts::new(parts), interval)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IntervalStats {
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub jitter: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PushReport<T> {
+    pub accepted: bool,
+    pub evicted: Vec<Item<T>>,
+    pub reallocated: bool,
+}
+
+/// Returned by `push_bounded` when the buffer is full, handing the item back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapacityError<T>(pub Item<T>);
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "buffer is full, item at {:?} was rejected", self.0.0)
+    }
+}
+
+impl<T: Debug> std::error::Error for CapacityError<T> {}
+
+/// Configurations rejected by `TimeVecBuilder::try_build`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// Seed item at this index is not newer than the one before it.
+    UnsortedData(usize),
+    /// A strict zero-limit window would discard every seed item.
+    DataInStrictZeroWindow,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsortedData(index) => write!(f, "seed item {index} is not newer than the previous one"),
+            Self::DataInStrictZeroWindow => write!(f, "strict zero-limit window cannot hold seed data"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl<T: Clone> Clone for TimeVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            limit: self.limit,
+            buffer: self.buffer.clone(),
+            sink: None,
+            policy: self.policy.clone(),
+            evicted: self.evicted,
+            strict: self.strict,
+            extremes: self.extremes.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for TimeVec<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimeVec")
+            .field("limit", &self.limit)
+            .field("buffer", &self.buffer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Pushes an item to the back. Items that are not newer than the back are dropped.
+impl<T> AddAssign<Item<T>> for TimeVec<T> {
+    #[inline]
+    fn add_assign(&mut self, (timestamp, item): Item<T>) {
+        self.push_back_checked(timestamp, item);
+    }
+}
+
+pub struct TimeVecBuilder<T> {
+    pub limit: Option<Duration>,
+    pub capacity: Option<usize>,
+    pub data: Vec<Item<T>>,
+    pub sink: Option<Sink<T>>,
+    pub policy: Option<Policy<T>>,
+    pub strict: bool,
+    extremes: Option<fn(&T) -> f64>,
+    pub limit_from_span: bool,
+}
+
+impl<T: Clone> Clone for TimeVecBuilder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            limit: self.limit,
+            capacity: self.capacity,
+            data: self.data.clone(),
+            sink: None,
+            policy: self.policy.clone(),
+            strict: self.strict,
+            extremes: self.extremes,
+            limit_from_span: self.limit_from_span,
+        }
+    }
+}
+
+impl<T: Debug> Debug for TimeVecBuilder<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimeVecBuilder")
+            .field("limit", &self.limit)
+            .field("capacity", &self.capacity)
+            .field("data", &self.data)
+            .field("strict", &self.strict)
+            .field("limit_from_span", &self.limit_from_span)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Default for TimeVecBuilder<T> {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            capacity: None,
+            data: Vec::new(),
+            sink: None,
+            policy: None,
+            strict: false,
+            extremes: None,
+            limit_from_span: false,
+        }
+    }
+}
+
+impl<T> TimeVecBuilder<T> {
+    pub fn with_limit(mut self, value: Duration) -> Self {
+        self.limit = Some(value);
+        self
+    }
+
+    pub fn with_limit_secs(self, value: u64) -> Self {
+        self.with_limit(Duration::from_secs(value))
+    }
+
+    pub fn with_limit_micros(self, value: u64) -> Self {
+        self.with_limit(Duration::from_micros(value))
+    }
+
+    pub fn with_limit_millis(self, value: u64) -> Self {
+        self.with_limit(Duration::from_millis(value))
+    }
+
+    pub fn with_limit_nanos(self, value: u64) -> Self {
+        self.with_limit(Duration::from_nanos(value))
+    }
+
+    /// Negative and NaN values clamp to zero, values too large for `Duration` to `Duration::MAX`.
+    pub fn with_limit_secs_f64(self, value: f64) -> Self {
+        let fallback = if value > 0.0 { Duration::MAX } else { Duration::ZERO };
+        self.with_limit(Duration::try_from_secs_f64(value).unwrap_or(fallback))
+    }
+
+    pub fn with_limit_mins(self, value: u64) -> Self {
+        self.with_limit_secs(value.saturating_mul(60))
+    }
+
+    pub fn with_limit_hours(self, value: u64) -> Self {
+        self.with_limit_secs(value.saturating_mul(60 * 60))
+    }
+
+    pub fn with_capacity(mut self, value: usize) -> Self {
+        self.capacity = Some(value);
+        self
+    }
+
+    pub fn with_data<I: IntoIterator<Item = Item<T>>>(mut self, items: I) -> Self {
+        self.data.extend(items);
+        self
+    }
+
+    /// Sets the limit to the span of the seed data at build time, zero with fewer than
+    /// two items. Overrides any explicit limit.
+    pub fn with_limit_from_span(mut self) -> Self {
+        self.limit_from_span = true;
+        self
+    }
+
+    /// Evicted items are passed to `sink` instead of the returned drains.
+    pub fn with_sink(mut self, sink: Sink<T>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// One slot per second of the limit, capped at 2^20 slots.
+    pub fn with_capacity_from_limit(self) -> Self {
+        const MAX_HINT: u64 = 1 << 20;
+
+        match self.limit {
+            Some(limit) => self.with_capacity(limit.as_secs().clamp(1, MAX_HINT) as usize),
+            None => self,
+        }
+    }
+
+    /// With a zero limit, a strict window stores nothing. Pushes are rejected before the
+    /// item is stored: checked pushes return `None`, `push_back` hands the item back in its
+    /// drain without counting an eviction or calling the sink, and bulk inserts hand back
+    /// all items the same way. Otherwise a zero limit keeps the newest item.
+    pub fn strict_window(mut self, value: bool) -> Self {
+        self.strict = value;
+        self
+    }
+
+    /// Replaces the time limit eviction on push with `policy`.
+    pub fn with_policy<P: EvictPolicy<T> + Send + Sync + UnwindSafe + RefUnwindSafe + 'static>(mut self, policy: P) -> Self {
+        self.policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Like `build`, but rejects seed data that would be silently dropped.
+    pub fn try_build(self) -> Result<TimeVec<T>, BuildError> {
+        if let Some(index) = self.data.windows(2).position(|w| w[0].0 >= w[1].0) {
+            return Err(BuildError::UnsortedData(index + 1));
+        }
+        let zero_limit = if self.limit_from_span {
+            self.data.len() < 2
+        } else {
+            self.limit.unwrap_or_default().is_zero()
+        };
+        if self.strict && zero_limit && !self.data.is_empty() {
+            return Err(BuildError::DataInStrictZeroWindow);
+        }
+        Ok(self.build())
+    }
+
+    pub fn build(self) -> TimeVec<T> {
+        let limit = match (self.limit_from_span, self.data.first(), self.data.last()) {
+            (true, Some(front), Some(back)) => back.0.saturating_sub(front.0),
+            (true, _, _) => Duration::ZERO,
+            (false, _, _) => self.limit.unwrap_or_default(),
+        };
+        let mut tv = TimeVec {
+            limit,
+            buffer: self.capacity
+                .map(VecDeque::<Item<T>>::with_capacity)
+                .unwrap_or_default(),
+            sink: self.sink,
+            policy: self.policy,
+            evicted: 0,
+            strict: self.strict,
+            extremes: self.extremes.map(|value| Extremes::from_buffer(value, &VecDeque::new())),
+        };
+
+        for (timestamp, item) in self.data {
+            tv.push_back_checked(timestamp, item);
+        }
+
+        tv
+    }
+}
+
+impl TimeVecBuilder<f64> {
+    /// Maintains the running min and max on push and eviction; see `TimeVec::current_max`.
+    pub fn track_extremes(mut self) -> Self {
+        self.extremes = Some(|value| *value);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Builds a buffer with `limit` from `(timestamp, value)` pairs, timestamps in `unit`s.
+    fn series<T, I>(limit: Duration, unit: fn(u64) -> Duration, items: I) -> TimeVec<T>
+    where
+        I: IntoIterator<Item = (u64, T)>,
+    {
+        let mut tv = TimeVec::builder().with_limit(limit).build();
+        for (t, value) in items {
+            tv.push_back(unit(t), value);
+        }
+        tv
+    }
+
+    #[test]
+    fn with_zero_limit() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit(Duration::ZERO)
+            .build();
+        assert!(tv.is_empty());
+
+        assert_eq!(tv.checked_duration(), None);
+        assert_eq!(tv.duration(), Duration::ZERO);
+        assert_eq!(tv.len(), 0);
+
+        tv.push_back(Duration::from_secs(1), ());
+        assert_eq!(tv.checked_duration(), Some(Duration::ZERO));
+        assert_eq!(tv.duration(), Duration::ZERO);
+        assert_eq!(tv.len(), 1);
+    }
+
+    #[test]
+    fn push_two_items_with_same_timestamp() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(1)
+            .build();
+        
+        tv.push_back_checked(Duration::from_secs(1), ());
+        assert_eq!(tv.len(), 1);
+
+        tv.push_back_checked(Duration::from_secs(1), ());
+        assert_eq!(tv.len(), 1);
+    }
+
+    #[test]
+    fn min_limit() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(1)
+            .build();
+        
+        tv.push_back(Duration::ZERO, ());
+        assert_eq!(tv.len(), 1);
+
+        tv.push_back(Duration::from_nanos(1), ());
+        assert_eq!(tv.len(), 2);
+
+        tv.push_back(Duration::from_nanos(2), ());
+        assert_eq!(tv.checked_duration(), Some(Duration::from_nanos(1)));
+        assert_eq!(tv.len(), 2);
+    }
+
+    #[test]
+    fn push_above_the_non_min_limit() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(3)
+            .build();
+        
+        tv.push_back(Duration::ZERO, ());
+        tv.push_back(Duration::from_nanos(1), ());
+        tv.push_back(Duration::from_nanos(2), ());
+        tv.push_back(Duration::from_nanos(3), ());
+        assert_eq!(tv.len(), 4);
+
+        tv.push_back(Duration::from_nanos(4), ());
+        assert_eq!(tv.len(), 4);
+    }
+
+    #[test]
+    fn push_near_duration_max() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit(Duration::MAX)
+            .build();
+
+        tv.push_back(Duration::ZERO, ());
+        tv.push_back(Duration::MAX - Duration::from_nanos(1), ());
+        tv.push_back(Duration::MAX, ());
+        assert_eq!(tv.len(), 3);
+        assert_eq!(tv.duration(), Duration::MAX);
+        assert_eq!(tv.checked_duration(), Some(Duration::MAX));
+        assert_eq!(tv.duration_from_back(&Duration::MAX), Some(Duration::ZERO));
+        assert_eq!(tv.push_back_checked(Duration::MAX, ()).map(|d| d.count()), None);
+
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(1)
+            .build();
+
+        tv.push_back(Duration::MAX - Duration::from_nanos(2), ());
+        tv.push_back(Duration::MAX, ());
+        assert_eq!(tv.len(), 1);
+        assert_eq!(tv.duration(), Duration::ZERO);
+    }
+
+    #[test]
+    fn since_last_gap() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.since_last(), None);
+
+        tv.push_back(Duration::from_secs(1), ());
+        assert_eq!(tv.since_last(), None);
+
+        tv.push_back(Duration::from_secs(2), ());
+        tv.push_back(Duration::from_millis(2500), ());
+        assert_eq!(tv.since_last(), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn clamp_to_smaller_limit() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(5)
+            .build();
+        assert_eq!(tv.clamp_to_limit().count(), 0);
+
+        for i in 0..5 {
+            tv.push_back(Duration::from_nanos(i), ());
+        }
+        assert_eq!(tv.clamp_to_limit().count(), 0);
+
+        tv.limit = Duration::from_nanos(2);
+        assert_eq!(tv.clamp_to_limit().count(), 2);
+        assert_eq!(tv.len(), 3);
+        assert_eq!(tv.iter_time().next(), Some(&Duration::from_nanos(2)));
+    }
+
+    #[test]
+    fn push_if_value_changed() {
+        let mut tv = TimeVec::<u32>::builder()
+            .with_limit_secs(10)
+            .build();
+
+        for (i, value) in [1, 1, 2, 2, 2, 3].into_iter().enumerate() {
+            tv.push_if(Duration::from_secs(i as u64), value, |back| {
+                back.map(|i| i.1 != value).unwrap_or(true)
+            });
+        }
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        assert!(tv.push_if(Duration::ZERO, 4, |_| true).is_none());
+        assert_eq!(tv.len(), 3);
+    }
+
+    #[test]
+    fn axis_range_uses_limit() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.axis_range(), None);
+
+        tv.push_back(Duration::from_secs(11), ());
+        tv.push_back(Duration::from_secs(12), ());
+        assert_eq!(tv.axis_range(), Some((Duration::from_secs(2), Duration::from_secs(12))));
+
+        tv.clear();
+        tv.push_back(Duration::from_secs(3), ());
+        assert_eq!(tv.axis_range(), Some((Duration::ZERO, Duration::from_secs(3))));
+    }
+
+    #[test]
+    fn build_with_data() {
+        let tv = TimeVec::<u32>::builder()
+            .with_limit_nanos(1)
+            .with_data([
+                (Duration::from_nanos(1), 1),
+                (Duration::from_nanos(2), 2),
+                (Duration::from_nanos(2), 0),
+                (Duration::from_nanos(3), 3),
+            ])
+            .build();
+
+        assert_eq!(tv.len(), 2);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn resample_hold_strings() {
+        let mut tv = TimeVec::<&str>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert!(tv.resample_hold(Duration::from_secs(1)).is_empty());
+
+        tv.push_back(Duration::from_secs(0), "idle");
+        assert_eq!(tv.resample_hold(Duration::from_secs(1)), vec![(Duration::ZERO, "idle")]);
+
+        tv.push_back(Duration::from_millis(1500), "busy");
+        tv.push_back(Duration::from_millis(3200), "idle");
+        assert_eq!(
+            tv.resample_hold(Duration::from_secs(1)),
+            vec![
+                (Duration::from_secs(0), "idle"),
+                (Duration::from_secs(1), "idle"),
+                (Duration::from_secs(2), "busy"),
+                (Duration::from_secs(3), "busy"),
+            ]
+        );
+    }
+
+    #[test]
+    fn front_back_endpoints() {
+        let mut tv = TimeVec::<u32>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.front_back(), None);
+
+        tv.push_back(Duration::from_secs(1), 1);
+        assert_eq!(tv.front_back(), Some((&(Duration::from_secs(1), 1), &(Duration::from_secs(1), 1))));
+
+        tv.push_back(Duration::from_secs(2), 2);
+        tv.push_back(Duration::from_secs(3), 3);
+        assert_eq!(tv.front_back(), Some((&(Duration::from_secs(1), 1), &(Duration::from_secs(3), 3))));
+    }
+
+    #[test]
+    fn monotonic_check() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert!(tv.is_monotonic());
+
+        tv.push_back(Duration::from_secs(1), ());
+        tv.push_back(Duration::from_secs(2), ());
+        assert!(tv.is_monotonic());
+
+        tv.push_back_unchecked(Duration::from_secs(2), ());
+        assert!(!tv.is_monotonic());
+    }
+
+    #[test]
+    fn cumulative_sum_prefix() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert!(tv.cumulative_sum().is_empty());
+
+        tv.push_back(Duration::from_secs(1), 1.0);
+        tv.push_back(Duration::from_secs(2), 2.0);
+        tv.push_back(Duration::from_secs(3), 3.0);
+        assert_eq!(
+            tv.cumulative_sum(),
+            vec![
+                (Duration::from_secs(1), 1.0),
+                (Duration::from_secs(2), 3.0),
+                (Duration::from_secs(3), 6.0),
+            ]
+        );
+        assert_eq!(tv.cumulative_sum().last().map(|i| i.1), Some(tv.sum()));
+    }
+
+    #[test]
+    fn recency_weighted_mean_favors_newest() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.recency_weighted_mean(Duration::from_secs(1)), None);
+
+        tv.push_back(Duration::from_secs(1), 0.0);
+        tv.push_back(Duration::from_secs(2), 10.0);
+        assert_eq!(tv.recency_weighted_mean(Duration::from_secs(1)), Some(20.0 / 3.0));
+
+        let mean = tv.recency_weighted_mean(Duration::from_nanos(1)).unwrap();
+        assert!((mean - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pairs_with_extractor() {
+        struct Reading {
+            celsius: f32,
+        }
+
+        let mut tv = TimeVec::<Reading>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_millis(500), Reading { celsius: 20.5 });
+        tv.push_back(Duration::from_secs(1), Reading { celsius: 21.0 });
+
+        assert_eq!(
+            tv.to_pairs_with(|r| r.celsius as f64),
+            vec![(0.5, 20.5), (1.0, 21.0)]
+        );
+    }
+
+    #[test]
+    fn evict_now_without_push() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(2)
+            .build();
+
+        tv.push_back(Duration::from_secs(1), ());
+        tv.push_back(Duration::from_secs(2), ());
+        tv.push_back(Duration::from_secs(3), ());
+        assert_eq!(tv.evict_now(Duration::from_secs(3)).count(), 0);
+
+        assert_eq!(tv.evict_now(Duration::from_secs(4)).count(), 1);
+        assert_eq!(tv.len(), 2);
+
+        assert_eq!(tv.evict_now(Duration::from_secs(10)).count(), 2);
+        assert!(tv.is_empty());
+    }
+
+    #[test]
+    fn capacity_for_rate() {
+        assert_eq!(TimeVec::<()>::capacity_for(Duration::from_secs(5), 100.0), 500);
+        assert_eq!(TimeVec::<()>::capacity_for(Duration::from_millis(1500), 1.0), 2);
+        assert_eq!(TimeVec::<()>::capacity_for(Duration::from_secs(5), -1.0), 0);
+        assert_eq!(TimeVec::<()>::capacity_for(Duration::from_secs(5), f64::NAN), 0);
+    }
+
+    #[test]
+    fn iter_from_cursor() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        let mut cursor = Duration::ZERO;
+        let mut seen = Vec::new();
+
+        for i in 1..=6 {
+            tv.push_back(Duration::from_secs(i), i);
+            if i % 2 == 0 {
+                for item in tv.iter_from(cursor) {
+                    seen.push(item.1);
+                    cursor = item.0;
+                }
+            }
+        }
+        assert_eq!(seen, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(tv.iter_from(cursor).len(), 0);
+    }
+
+    #[test]
+    fn range_bounds() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 1..=5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+        let s = Duration::from_secs;
+        let values = |r: Vec<&Item<u64>>| r.into_iter().map(|i| i.1).collect::<Vec<_>>();
+
+        assert_eq!(values(tv.range(..).collect()), vec![1, 2, 3, 4, 5]);
+        assert_eq!(values(tv.range(s(2)..s(4)).collect()), vec![2, 3]);
+        assert_eq!(values(tv.range(s(2)..=s(4)).collect()), vec![2, 3, 4]);
+        assert_eq!(values(tv.range(..s(3)).collect()), vec![1, 2]);
+        assert_eq!(values(tv.range(..=s(3)).collect()), vec![1, 2, 3]);
+        assert_eq!(values(tv.range(s(4)..).collect()), vec![4, 5]);
+        assert_eq!(values(tv.range((Bound::Excluded(s(4)), Bound::Unbounded)).collect()), vec![5]);
+        assert_eq!(tv.range(s(4)..s(2)).len(), 0);
+        assert_eq!(tv.range(s(6)..).len(), 0);
+    }
+
+    #[test]
+    fn snapshots_align() {
+        let mut tv = TimeVec::<String>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_secs(1), "a".to_string());
+        tv.push_back(Duration::from_secs(2), "b".to_string());
+
+        let data = tv.snapshot_data();
+        let time = tv.snapshot_time();
+        assert_eq!(data.len(), time.len());
+        for (index, item) in tv.iter().enumerate() {
+            assert_eq!(time[index], item.0);
+            assert_eq!(data[index], item.1);
+        }
+    }
+
+    #[test]
+    fn extremes_by_key() {
+        struct Sample {
+            hits: u32,
+            misses: u32,
+        }
+
+        let mut tv = TimeVec::<Sample>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert!(tv.max_by_key(|i| i.1.hits).is_none());
+
+        tv.push_back(Duration::from_secs(1), Sample { hits: 1, misses: 3 });
+        tv.push_back(Duration::from_secs(2), Sample { hits: 5, misses: 1 });
+        tv.push_back(Duration::from_secs(3), Sample { hits: 2, misses: 4 });
+        tv.push_back(Duration::from_secs(4), Sample { hits: 4, misses: 2 });
+
+        let total = |i: &Item<Sample>| i.1.hits + i.1.misses;
+        assert_eq!(tv.max_by_key(total).map(|i| i.0), Some(Duration::from_secs(2)));
+        assert_eq!(tv.min_by_key(total).map(|i| i.0), Some(Duration::from_secs(1)));
+        assert_eq!(tv.max_by_key(|i| i.1.misses).map(|i| i.0), Some(Duration::from_secs(3)));
+        assert_eq!(tv.min_by_key(|i| i.1.hits / 2).map(|i| i.0), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn reconfigure_limit_and_capacity() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(10)
+            .with_capacity(100)
+            .build();
+        for i in 0..10 {
+            tv.push_back(Duration::from_nanos(i), ());
+        }
+        assert!(tv.capacity() >= 100);
+
+        assert_eq!(tv.reconfigure(Duration::from_nanos(2), 5).count(), 7);
+        assert_eq!(tv.len(), 3);
+        assert!(tv.capacity() >= 10 && tv.capacity() < 100);
+
+        assert_eq!(tv.reconfigure(Duration::from_nanos(2), 50).count(), 0);
+        assert!(tv.capacity() >= 50);
+    }
+
+    #[test]
+    fn fold_time_weighted_sum() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.fold_time_weighted(0.0, |acc, v, dt| acc + v * dt.as_secs_f64()), 0.0);
+
+        tv.push_back(Duration::from_secs(0), 1.0);
+        tv.push_back(Duration::from_secs(1), 2.0);
+        tv.push_back(Duration::from_secs(3), 3.0);
+        assert_eq!(tv.fold_time_weighted(0.0, |acc, v, dt| acc + v * dt.as_secs_f64()), 5.0);
+
+        let widths = tv.fold_time_weighted(Vec::new(), |mut acc, _, dt| {
+            acc.push(dt);
+            acc
+        });
+        assert_eq!(widths, vec![Duration::from_secs(1), Duration::from_secs(2), Duration::ZERO]);
+    }
+
+    #[test]
+    fn compact_burst() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..11 {
+            tv.push_back(Duration::from_nanos(i), i);
+        }
+
+        tv.compact(Duration::from_nanos(3));
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![0, 3, 6, 9, 10]);
+        assert!(tv.is_monotonic());
+    }
+
+    #[test]
+    fn sorted_by_value_copy() {
+        let mut tv = TimeVec::<u32>::builder()
+            .with_limit_secs(10)
+            .build();
+        for (i, value) in [3, 1, 2, 1].into_iter().enumerate() {
+            tv.push_back(Duration::from_secs(i as u64), value);
+        }
+
+        let sorted = tv.sorted_by_value();
+        assert_eq!(
+            sorted,
+            vec![
+                (Duration::from_secs(1), 1),
+                (Duration::from_secs(3), 1),
+                (Duration::from_secs(2), 2),
+                (Duration::from_secs(0), 3),
+            ]
+        );
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![3, 1, 2, 1]);
+        assert_eq!(tv.sorted_by_value_by(|a, b| b.cmp(a))[0], (Duration::ZERO, 3));
+    }
+
+    #[test]
+    fn push_ref_returns_back() {
+        let mut tv = TimeVec::<u32>::builder()
+            .with_limit_nanos(1)
+            .build();
+
+        assert_eq!(tv.push_ref(Duration::from_nanos(1), 1), Some(&(Duration::from_nanos(1), 1)));
+        assert_eq!(tv.push_ref(Duration::from_nanos(5), 5), Some(&(Duration::from_nanos(5), 5)));
+        assert_eq!(tv.push_ref(Duration::from_nanos(5), 6), None);
+        assert_eq!(tv.len(), 1);
+    }
+
+    #[test]
+    fn iter_range_idx_clamps() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        assert_eq!(tv.iter_range_idx(0..2).map(|i| i.1).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(tv.iter_range_idx(4..6).map(|i| i.1).collect::<Vec<_>>(), vec![4]);
+        assert_eq!(tv.iter_range_idx(6..8).len(), 0);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = tv.iter_range_idx(3..1).len();
+        assert_eq!(reversed, 0);
+    }
+
+    #[test]
+    fn value_at_or_before_holds() {
+        let mut tv = TimeVec::<&str>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.value_at_or_before(Duration::from_secs(1)), None);
+
+        tv.push_back(Duration::from_secs(1), "a");
+        tv.push_back(Duration::from_secs(3), "b");
+
+        assert_eq!(tv.value_at_or_before(Duration::ZERO), None);
+        assert_eq!(tv.value_at_or_before(Duration::from_secs(1)), Some(&"a"));
+        assert_eq!(tv.value_at_or_before(Duration::from_secs(2)), Some(&"a"));
+        assert_eq!(tv.value_at_or_before(Duration::from_secs(3)), Some(&"b"));
+        assert_eq!(tv.value_at_or_before(Duration::from_secs(9)), Some(&"b"));
+    }
+
+    #[test]
+    fn trim_to_shorter_coverage() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(5)
+            .build();
+        for i in 0..=5 {
+            tv.push_back(Duration::from_nanos(i), ());
+        }
+        assert_eq!(tv.duration(), Duration::from_nanos(5));
+
+        assert_eq!(tv.trim_to_coverage(Duration::from_nanos(2)).count(), 3);
+        assert_eq!(tv.duration(), Duration::from_nanos(2));
+        assert_eq!(tv.limit, Duration::from_nanos(5));
+    }
+
+    #[test]
+    fn bracket_query() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert!(tv.bracket(Duration::ZERO).is_none());
+
+        for i in 1..=3 {
+            tv.push_back(Duration::from_secs(i * 2), i);
+        }
+        let values = |b: Option<(&Item<u64>, &Item<u64>)>| b.map(|(a, b)| (a.1, b.1));
+
+        assert_eq!(values(tv.bracket(Duration::from_secs(3))), Some((1, 2)));
+        assert_eq!(values(tv.bracket(Duration::from_secs(4))), Some((2, 2)));
+        assert_eq!(values(tv.bracket(Duration::from_secs(2))), Some((1, 1)));
+        assert_eq!(values(tv.bracket(Duration::from_secs(6))), Some((3, 3)));
+        assert_eq!(values(tv.bracket(Duration::from_secs(1))), None);
+        assert_eq!(values(tv.bracket(Duration::from_secs(7))), None);
+    }
+
+    #[test]
+    fn consume_sorted() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_nanos(3)
+            .build();
+        for i in 0..6 {
+            tv.push_back(Duration::from_nanos(i), i);
+        }
+
+        let items: Vec<_> = tv.into_iter_sorted().collect();
+        assert_eq!(items.len(), 4);
+        assert!(items.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(items[0], (Duration::from_nanos(2), 2));
+    }
+
+    #[test]
+    fn add_assign_pushes() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+
+        tv += (Duration::from_secs(1), 1.0);
+        tv += (Duration::from_secs(2), 2.0);
+        tv += (Duration::from_secs(2), 3.0);
+        assert_eq!(tv.len(), 2);
+        assert_eq!(tv.sum(), 3.0);
+    }
+
+    #[test]
+    fn overlap_detection() {
+        let s = Duration::from_secs;
+        let build = |range: Range<u64>| series(s(10), s, range.map(|i| (i, ())));
+
+        let a = build(0..3);
+        assert_eq!(a.overlap_range(&build(2..5)), Some((s(2), s(2))));
+        assert_eq!(a.overlap_range(&build(1..5)), Some((s(1), s(2))));
+        assert_eq!(build(1..2).overlap_range(&a), Some((s(1), s(1))));
+        assert!(!a.overlaps(&build(3..5)));
+        assert!(!a.overlaps(&build(0..0)));
+        assert!(!build(0..0).overlaps(&a));
+    }
+
+    #[test]
+    fn retain_even_timestamps() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..6 {
+            tv.push_back(Duration::from_nanos(i), i);
+        }
+
+        tv.retain_time(|t| t.as_nanos() % 2 == 0);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![0, 2, 4]);
+
+        tv.retain(|item| item.1 > 0);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![2, 4]);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn to_ndarray_columns() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_millis(500), 1.5);
+        tv.push_back(Duration::from_secs(2), -2.0);
+
+        let (time, data) = tv.to_ndarray();
+        assert_eq!(time.len(), 2);
+        assert_eq!(data.len(), 2);
+        assert_eq!(time[0], 0.5);
+        assert_eq!(data[1], -2.0);
+    }
+
+    #[test]
+    fn downsample_mean_buckets() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert!(tv.downsample_mean(Duration::from_secs(1)).is_empty());
+
+        tv.push_back(Duration::from_millis(1000), 1.0);
+        tv.push_back(Duration::from_millis(1400), 3.0);
+        tv.push_back(Duration::from_millis(1900), 2.0);
+        tv.push_back(Duration::from_millis(3100), 5.0);
+        tv.push_back(Duration::from_millis(3500), 7.0);
+
+        assert_eq!(
+            tv.downsample_mean(Duration::from_secs(1)),
+            vec![
+                (Duration::from_secs(1), 2.0),
+                (Duration::from_secs(3), 6.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn concat_interleaved() {
+        let build = |items: &[(u64, char)]| {
+            let mut tv = TimeVec::<char>::builder()
+                .with_limit_secs(10)
+                .build();
+            for (t, c) in items {
+                tv.push_back(Duration::from_nanos(*t), *c);
+            }
+            tv
+        };
+
+        let tv = TimeVec::concat(
+            [
+                build(&[(0, 'a'), (3, 'd'), (6, 'g')]),
+                build(&[(1, 'b'), (4, 'e'), (6, 'x')]),
+                build(&[(2, 'c'), (5, 'f'), (7, 'h')]),
+            ],
+            Duration::from_nanos(5),
+        );
+        assert_eq!(tv.iter_data().collect::<String>(), "cdefgh");
+        assert!(tv.is_monotonic());
+    }
+
+    #[test]
+    fn local_extrema_sawtooth() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for (i, value) in [3.0, 0.0, 1.0, 2.0, 0.0, 1.0, 1.0, 0.0].into_iter().enumerate() {
+            tv.push_back(Duration::from_secs(i as u64), value);
+        }
+
+        let times = |items: Vec<&Item<f64>>| items.iter().map(|i| i.0.as_secs()).collect::<Vec<_>>();
+        assert_eq!(times(tv.local_maxima()), vec![3]);
+        assert_eq!(times(tv.local_minima()), vec![1, 4]);
+
+        tv.clear();
+        tv.push_back(Duration::from_secs(1), 1.0);
+        assert!(tv.local_maxima().is_empty());
+    }
+
+    #[test]
+    fn sink_receives_evicted() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(1)
+            .with_sink(Box::new(move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }))
+            .build();
+
+        for i in 0..5 {
+            assert_eq!(tv.push_back(Duration::from_nanos(i), ()).count(), 0);
+        }
+        assert_eq!(count.load(Ordering::Relaxed), 3);
+        assert_eq!(tv.len(), 2);
+
+        let mut copy = tv.clone();
+        copy.push_back(Duration::from_nanos(10), ());
+        assert_eq!(count.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn count_fresh_during_silence() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(2)
+            .build();
+        for i in 1..=3 {
+            tv.push_back(Duration::from_secs(i), ());
+        }
+
+        assert_eq!(tv.count_fresh(Duration::from_secs(3)), 3);
+        assert_eq!(tv.count_fresh(Duration::from_secs(4)), 2);
+        assert_eq!(tv.count_fresh(Duration::from_secs(5)), 1);
+        assert_eq!(tv.count_fresh(Duration::from_secs(6)), 0);
+        assert_eq!(tv.len(), 3);
+    }
+
+    #[test]
+    fn truncate_front_keeps_oldest() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        assert_eq!(tv.truncate_front(2).map(|i| i.1).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(tv.len(), 2);
+        assert_eq!(tv.front_back().map(|(_, back)| back.1), Some(1));
+
+        assert_eq!(tv.truncate_front(5).count(), 0);
+        assert_eq!(tv.truncate_front(0).count(), 2);
+        assert!(tv.is_empty());
+    }
+
+    #[test]
+    fn interpolate_many_ramp() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.interpolate_many(&[Duration::ZERO]), vec![None]);
+
+        tv.push_back(Duration::from_secs(1), 10.0);
+        tv.push_back(Duration::from_secs(2), 20.0);
+        tv.push_back(Duration::from_secs(4), 0.0);
+
+        let queries = [0, 1000, 1500, 2000, 3000, 3500, 4000, 5000].map(Duration::from_millis);
+        assert_eq!(
+            tv.interpolate_many(&queries),
+            vec![None, Some(10.0), Some(15.0), Some(20.0), Some(10.0), Some(5.0), Some(0.0), None]
+        );
+        for (at, value) in queries.iter().zip(tv.interpolate_many(&queries)) {
+            assert_eq!(tv.interpolate(*at), value);
+        }
+    }
+
+    #[test]
+    fn dispersion_metrics() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_secs(1), 2.0);
+        assert_eq!(tv.stddev(), None);
+
+        for (i, value) in [4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].into_iter().enumerate() {
+            tv.push_back(Duration::from_secs(i as u64 + 2), value);
+        }
+        assert_eq!(tv.mean(), Some(5.0));
+        assert_eq!(tv.variance(), Some(32.0 / 7.0));
+        assert_eq!(tv.stddev(), Some((32.0f64 / 7.0).sqrt()));
+        assert_eq!(tv.coefficient_of_variation(), Some((32.0f64 / 7.0).sqrt() / 5.0));
+
+        tv.clear();
+        tv.push_back(Duration::from_secs(1), -1.0);
+        tv.push_back(Duration::from_secs(2), 1.0);
+        assert_eq!(tv.coefficient_of_variation(), None);
+    }
+
+    #[test]
+    fn first_crossing_ramp() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for (i, value) in [0.0, 4.0, 8.0, 2.0].into_iter().enumerate() {
+            tv.push_back(Duration::from_secs(i as u64), value);
+        }
+
+        assert_eq!(tv.first_crossing(5.0, true), Some(Duration::from_millis(1250)));
+        assert_eq!(tv.first_crossing(5.0, false), Some(Duration::from_millis(2500)));
+        assert_eq!(tv.first_crossing(10.0, true), None);
+
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_secs(0), f64::NEG_INFINITY);
+        tv.push_back(Duration::from_secs(1), 1.0);
+        tv.push_back(Duration::from_secs(2), f64::INFINITY);
+        tv.push_back(Duration::from_secs(3), -1.0);
+        assert_eq!(tv.first_crossing(0.0, true), Some(Duration::from_secs(1)));
+        assert_eq!(tv.first_crossing(0.0, false), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn mode_of_states() {
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        enum State {
+            Up,
+            Down,
+            Flapping,
+        }
+
+        let mut tv = TimeVec::<State>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.mode(), None);
+
+        tv.push_back(Duration::from_secs(1), State::Down);
+        tv.push_back(Duration::from_secs(2), State::Up);
+        assert_eq!(tv.mode(), Some(&State::Down));
+
+        tv.push_back(Duration::from_secs(3), State::Flapping);
+        tv.push_back(Duration::from_secs(4), State::Up);
+        tv.push_back(Duration::from_secs(5), State::Up);
+        assert_eq!(tv.mode(), Some(&State::Up));
+    }
+
+    #[test]
+    fn time_as_secs_f64() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_millis(1500), ());
+        tv.push_back(Duration::from_millis(2250), ());
+
+        assert_eq!(tv.iter_time_secs_f64().len(), 2);
+        assert_eq!(
+            tv.iter_time_secs_f64().collect::<Vec<_>>(),
+            tv.iter_time().map(|t| t.as_secs_f64()).collect::<Vec<_>>()
+        );
+        assert_eq!(tv.iter_time_secs_f64_relative().collect::<Vec<_>>(), vec![0.0, 0.75]);
+    }
+
+    #[test]
+    fn merge_with_sums_collisions() {
+        let build = |items: &[(u64, u32)]| {
+            let mut tv = TimeVec::<u32>::builder()
+                .with_limit_secs(10)
+                .build();
+            for (t, v) in items {
+                tv.push_back(Duration::from_secs(*t), *v);
+            }
+            tv
+        };
+
+        let a = build(&[(1, 1), (2, 2), (4, 4)]);
+        let b = build(&[(2, 20), (3, 30), (4, 40), (5, 50)]);
+        let tv = a.merge_with(b, |a, b| a + b);
+        assert_eq!(
+            tv.iter().map(|i| (i.0.as_secs(), i.1)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 22), (3, 30), (4, 44), (5, 50)]
+        );
+        assert!(tv.is_monotonic());
+    }
+
+    #[test]
+    fn elapsed_since_front_with_clock() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.elapsed_since_front(Duration::from_secs(1)), None);
+
+        tv.push_back(Duration::from_secs(2), ());
+        tv.push_back(Duration::from_secs(4), ());
+        assert_eq!(tv.duration(), Duration::from_secs(2));
+        assert_eq!(tv.elapsed_since_front(Duration::from_secs(7)), Some(Duration::from_secs(5)));
+        assert_eq!(tv.elapsed_since_front(Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn interval_stats_irregular() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_millis(0), ());
+        assert_eq!(tv.interval_stats(), None);
+
+        tv.push_back(Duration::from_millis(100), ());
+        tv.push_back(Duration::from_millis(150), ());
+        tv.push_back(Duration::from_millis(400), ());
+        assert_eq!(
+            tv.interval_stats(),
+            Some(IntervalStats {
+                min: Duration::from_millis(50),
+                max: Duration::from_millis(250),
+                mean: Duration::from_nanos(133_333_333),
+                jitter: Duration::from_millis(200),
+            })
+        );
+    }
+
+    #[test]
+    fn clamp_values_in_place() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for (i, value) in [-5.0, 0.5, 5.0, f64::NAN].into_iter().enumerate() {
+            tv.push_back(Duration::from_secs(i as u64), value);
+        }
+
+        tv.clamp_values(0.0, 1.0);
+        let values: Vec<f64> = tv.iter_data().copied().collect();
+        assert_eq!(values[..3], [0.0, 0.5, 1.0]);
+        assert!(values[3].is_nan());
+        assert_eq!(tv.iter_time().last(), Some(&Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn sanitize_nan() {
+        let build = || {
+            let mut tv = TimeVec::<f64>::builder()
+                .with_limit_secs(10)
+                .build();
+            for (i, value) in [1.0, f64::NAN, 3.0, f64::NAN].into_iter().enumerate() {
+                tv.push_back(Duration::from_secs(i as u64), value);
+            }
+            tv
+        };
+
+        let mut tv = build();
+        assert!(tv.sum().is_nan());
+        assert_eq!(tv.replace_nan(0.0), 2);
+        assert_eq!(tv.sum(), 4.0);
+        assert_eq!(tv.replace_nan(0.0), 0);
+
+        let mut tv = build();
+        assert_eq!(tv.drop_nan(), 2);
+        assert_eq!(tv.len(), 2);
+        assert_eq!(tv.mean(), Some(2.0));
+        assert!(tv.is_monotonic());
+    }
+
+    #[test]
+    fn covered_duration_in_sub_range() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(100)
+            .build();
+        for i in [1, 12, 15, 30] {
+            tv.push_back(Duration::from_secs(i), ());
+        }
+        let s = Duration::from_secs;
+
+        assert_eq!(tv.covered_duration_in(s(10), s(20)), s(3));
+        assert_eq!(tv.covered_duration_in(s(12), s(30)), s(18));
+        assert_eq!(tv.covered_duration_in(s(13), s(20)), Duration::ZERO);
+        assert_eq!(tv.covered_duration_in(s(20), s(10)), Duration::ZERO);
+    }
+
+    #[test]
+    fn compact_clone_has_no_slack() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .with_capacity(64)
+            .build();
+        for i in 0..5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        let copy = tv.compact_clone();
+        assert_eq!(copy.capacity(), copy.len());
+        assert_eq!(copy.limit, tv.limit);
+        assert!(copy.iter().eq(tv.iter()));
+        assert!(tv.capacity() >= 64);
+    }
+
+    #[test]
+    fn rolling_extremes_match_brute_force() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(100)
+            .build();
+        let mut seed = 7u64;
+        let mut t = 0;
+        for _ in 0..50 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            t += 1 + (seed >> 60);
+            tv.push_back(Duration::from_millis(t), (seed >> 33) as f64 / 1e6);
+        }
+
+        let window = Duration::from_millis(20);
+        let brute = |pick: fn(f64, f64) -> f64| {
+            tv.iter()
+                .map(|item| {
+                    let value = tv
+                        .range(item.0.saturating_sub(window)..=item.0)
+                        .map(|i| i.1)
+                        .reduce(pick)
+                        .unwrap();
+                    (item.0, value)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tv.rolling_max(window), brute(f64::max));
+        assert_eq!(tv.rolling_min(window), brute(f64::min));
+    }
+
+    #[test]
+    fn prune_to_memory_budget() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..10 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+        let item = size_of::<Item<u64>>();
+        assert_eq!(tv.memory_bytes(), 10 * item);
+
+        assert_eq!(tv.prune_to_bytes(3 * item + 1).count(), 7);
+        assert!(tv.memory_bytes() <= 3 * item + 1);
+        assert_eq!(tv.iter_data().next(), Some(&7));
+
+        assert_eq!(tv.prune_to_bytes(usize::MAX).count(), 0);
+        assert_eq!(tv.prune_to_bytes(0).count(), 3);
+    }
+
+    #[test]
+    fn iter_mut_with_time_drift() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..3 {
+            tv.push_back(Duration::from_secs(i), 10.0);
+        }
+
+        for (t, value) in tv.iter_mut_with_time() {
+            *value -= 0.5 * t.as_secs_f64();
+        }
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![10.0, 9.5, 9.0]);
+    }
+
+    #[test]
+    fn push_report_flags() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(100)
+            .with_capacity(2)
+            .build();
+        let capacity = tv.capacity() as u64;
+        for i in 0..capacity {
+            assert!(!tv.push_report(Duration::from_secs(i), i).reallocated);
+        }
+        let report = tv.push_report(Duration::from_secs(capacity), capacity);
+        assert_eq!(report, PushReport { accepted: true, evicted: vec![], reallocated: true });
+
+        let report = tv.push_report(Duration::ZERO, 0);
+        assert!(!report.accepted);
+
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_nanos(1)
+            .build();
+        tv.push_back(Duration::from_nanos(1), 1);
+        let report = tv.push_report(Duration::from_nanos(5), 5);
+        assert_eq!(report.evicted, vec![(Duration::from_nanos(1), 1)]);
+    }
+
+    #[test]
+    fn filter_value_band_ramp() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..10 {
+            tv.push_back(Duration::from_secs(i), i as f64);
+        }
+
+        let values = |items: Vec<&Item<f64>>| items.iter().map(|i| i.1).collect::<Vec<_>>();
+        assert_eq!(values(tv.filter_value_band(3.0, 5.5)), vec![3.0, 4.0, 5.0]);
+        assert!(tv.filter_value_band(5.0, 3.0).is_empty());
+    }
+
+    #[test]
+    fn normalize_min_max() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.normalize_values();
+
+        for (i, value) in [2.0, 4.0, 6.0].into_iter().enumerate() {
+            tv.push_back(Duration::from_secs(i as u64), value);
+        }
+        tv.normalize_values();
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![0.0, 0.5, 1.0]);
+
+        tv.clamp_values(0.5, 0.5);
+        tv.normalize_values();
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn diff_aligned_series() {
+        let build = |items: &[(u64, f64)]| {
+            let mut tv = TimeVec::<f64>::builder()
+                .with_limit_secs(10)
+                .build();
+            for (t, v) in items {
+                tv.push_back(Duration::from_secs(*t), *v);
+            }
+            tv
+        };
+
+        let a = build(&[(1, 1.0), (2, 4.0), (3, 9.0)]);
+        let b = build(&[(1, 1.0), (2, 3.0), (3, 10.0)]);
+        assert_eq!(
+            a.diff_against(&b),
+            Some(vec![
+                (Duration::from_secs(1), 0.0),
+                (Duration::from_secs(2), 1.0),
+                (Duration::from_secs(3), -1.0),
+            ])
+        );
+        assert_eq!(a.diff_against(&build(&[(1, 1.0)])), None);
+        assert_eq!(a.diff_against(&build(&[(1, 1.0), (2, 4.0), (4, 9.0)])), None);
+    }
+
+    #[test]
+    fn reserve_exact_capacity() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_secs(1), 1);
+        tv.push_back(Duration::from_secs(2), 2);
+        tv.push_back(Duration::from_secs(3), 3);
+
+        let mut tv = tv.compact_clone();
+        tv.reserve_exact(10);
+        assert_eq!(tv.capacity(), tv.len() + 10);
+    }
+
+    #[test]
+    fn eviction_counter() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(2)
+            .build();
+        for i in 0..10 {
+            tv.push_back(Duration::from_nanos(i), ());
+        }
+        assert_eq!(tv.total_evicted(), 7);
+
+        tv.clear();
+        assert_eq!(tv.total_evicted(), 7);
+
+        tv.push_back(Duration::from_nanos(20), ());
+        tv.push_back(Duration::from_nanos(30), ());
+        assert_eq!(tv.total_evicted(), 8);
+
+        tv.reset_stats();
+        assert_eq!(tv.total_evicted(), 0);
+    }
+
+    #[test]
+    fn iter_until_prefix() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 1..=5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        let pivot = Duration::from_secs(3);
+        assert_eq!(tv.iter_until(pivot).map(|i| i.1).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(tv.iter_until(pivot).len() + tv.iter_from(pivot).len(), tv.len() - 1);
+        assert_eq!(tv.iter_until(Duration::ZERO).len(), 0);
+        assert_eq!(tv.iter_until(Duration::from_secs(9)).len(), 5);
+    }
+
+    #[test]
+    fn median_absolute_deviation() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.mad(), None);
+
+        for (i, value) in [1.0, 1.0, 2.0, 2.0, 4.0, 6.0, 9.0].into_iter().enumerate() {
+            tv.push_back(Duration::from_secs(i as u64), value);
+        }
+        assert_eq!(tv.median(), Some(2.0));
+        assert_eq!(tv.mad(), Some(1.0));
+
+        tv.push_back(Duration::from_secs(7), 100.0);
+        assert_eq!(tv.median(), Some(3.0));
+        assert_eq!(tv.mad(), Some(2.0));
+    }
+
+    #[test]
+    fn push_if_changed_deadband() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for (i, value) in [1.0, 1.01, 0.99, 1.02, 1.0].into_iter().enumerate() {
+            tv.push_if_changed(Duration::from_secs(i as u64), value, 0.05);
+        }
+        assert_eq!(tv.len(), 1);
+
+        assert!(tv.push_if_changed(Duration::from_secs(6), 1.1, 0.05).is_some());
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![1.0, 1.1]);
+
+        assert!(tv.push_if_changed(Duration::from_secs(7), f64::NAN, 0.05).is_some());
+        assert!(tv.push_if_changed(Duration::from_secs(8), 1.1, 0.05).is_some());
+        assert_eq!(tv.len(), 4);
+    }
+
+    #[test]
+    fn resample_onto_finer_grid() {
+        let mut coarse = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        coarse.push_back(Duration::from_secs(1), 0.0);
+        coarse.push_back(Duration::from_secs(3), 4.0);
+
+        let mut fine = TimeVec::<&str>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..5 {
+            fine.push_back(Duration::from_secs(i), "tick");
+        }
+
+        assert_eq!(
+            coarse.resample_onto(&fine),
+            vec![
+                (Duration::from_secs(0), None),
+                (Duration::from_secs(1), Some(0.0)),
+                (Duration::from_secs(2), Some(2.0)),
+                (Duration::from_secs(3), Some(4.0)),
+                (Duration::from_secs(4), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn correlation_signs() {
+        let build = |values: &[f64]| {
+            let mut tv = TimeVec::<f64>::builder()
+                .with_limit_secs(10)
+                .build();
+            for (i, v) in values.iter().enumerate() {
+                tv.push_back(Duration::from_secs(i as u64), *v);
+            }
+            tv
+        };
+
+        let a = build(&[1.0, 2.0, 3.0, 4.0]);
+        assert!((a.correlation(&build(&[2.0, 4.0, 6.0, 8.0])).unwrap() - 1.0).abs() < 1e-12);
+        assert!((a.correlation(&build(&[8.0, 6.0, 4.0, 2.0])).unwrap() + 1.0).abs() < 1e-12);
+        assert_eq!(a.correlation(&build(&[1.0, 1.0, 1.0, 1.0])), None);
+        assert_eq!(a.correlation(&build(&[1.0, 2.0])), None);
+        assert_eq!(build(&[1.0]).correlation(&build(&[1.0])), None);
+    }
+
+    #[test]
+    fn trailing_window_indices() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in [0, 1, 2, 5, 6] {
+            tv.push_back(Duration::from_secs(i), ());
+        }
+
+        assert_eq!(
+            tv.window_indices(Duration::from_secs(1)).collect::<Vec<_>>(),
+            vec![(0, 1), (0, 2), (1, 3), (3, 4), (3, 5)]
+        );
+    }
+
+    #[test]
+    fn capacity_from_limit() {
+        let tv = TimeVec::<u64>::builder()
+            .with_limit_secs(60)
+            .with_capacity_from_limit()
+            .build();
+        assert!(tv.capacity() >= 60 && tv.capacity() < 120);
+
+        let builder = TimeVec::<u64>::builder().with_capacity_from_limit();
+        assert_eq!(builder.capacity, None);
+
+        let builder = TimeVec::<u64>::builder()
+            .with_limit_millis(10)
+            .with_capacity_from_limit();
+        assert_eq!(builder.capacity, Some(1));
+
+        let tv = TimeVec::<u64>::builder()
+            .with_limit(Duration::MAX)
+            .with_capacity_from_limit()
+            .build();
+        assert!(tv.capacity() >= 1 << 20);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_lines_export() {
+        let mut tv = TimeVec::<&str>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::from_nanos(5), "a");
+        tv.push_back(Duration::from_secs(1), "b\"c");
+
+        let jsonl = tv.to_jsonl().unwrap();
+        assert_eq!(jsonl.lines().count(), tv.len());
+
+        let line: serde_json::Value = serde_json::from_str(jsonl.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(line["t_nanos"], 1_000_000_000u64);
+        assert_eq!(line["value"], "b\"c");
+    }
+
+    #[test]
+    fn insert_many_shuffled() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_nanos(6)
+            .build();
+        tv.push_back(Duration::from_nanos(2), 2);
+        tv.push_back(Duration::from_nanos(5), 5);
+
+        let batch = [7, 0, 4, 1, 5, 8, 3].map(|i| (Duration::from_nanos(i), i * 10));
+        assert_eq!(tv.insert_many(batch).map(|i| i.1).collect::<Vec<_>>(), vec![0, 10]);
+
+        assert!(tv.is_monotonic());
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![2, 30, 40, 5, 70, 80]);
+    }
+
+    #[test]
+    fn trim_both_ends() {
+        let build = || {
+            let mut tv = TimeVec::<u64>::builder()
+                .with_limit_secs(10)
+                .build();
+            for i in 0..=5 {
+                tv.push_back(Duration::from_nanos(i), i);
+            }
+            tv
+        };
+        let values = |items: Vec<Item<u64>>| items.into_iter().map(|i| i.1).collect::<Vec<_>>();
+
+        let mut tv = build();
+        let (head, tail) = tv.trim_ends(Duration::from_nanos(1), Duration::from_nanos(1));
+        assert_eq!((values(head), values(tail)), (vec![0], vec![5]));
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        let mut tv = build();
+        let (head, tail) = tv.trim_ends(Duration::from_nanos(4), Duration::from_nanos(4));
+        assert_eq!((values(head), values(tail)), (vec![0, 1, 2, 3], vec![4, 5]));
+        assert!(tv.is_empty());
+    }
+
+    #[test]
+    fn approx_eq_with_tolerance() {
+        let build = |offset: f64, last: u64| {
+            let mut tv = TimeVec::<f64>::builder()
+                .with_limit_secs(10)
+                .build();
+            tv.push_back(Duration::from_secs(1), 1.0 + offset);
+            tv.push_back(Duration::from_secs(last), 2.0 - offset);
+            tv
+        };
+
+        assert!(build(0.0, 2).approx_eq_values(&build(1e-9, 2), 1e-6));
+        assert!(!build(0.0, 2).approx_eq_values(&build(1e-3, 2), 1e-6));
+        assert!(!build(0.0, 2).approx_eq_values(&build(0.0, 3), 1e-6));
+    }
+
+    #[test]
+    fn extend_from_newer_items() {
+        let build = |range: Range<u64>, limit: u64| {
+            let mut tv = TimeVec::<u64>::builder()
+                .with_limit_nanos(limit)
+                .build();
+            for i in range {
+                tv.push_back(Duration::from_nanos(i), i);
+            }
+            tv
+        };
+
+        let mut tv = build(0..4, 4);
+        let other = build(2..8, 100);
+        assert_eq!(tv.extend_from(&other).map(|i| i.1).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![3, 4, 5, 6, 7]);
+        assert_eq!(tv.limit, Duration::from_nanos(4));
+        assert_eq!(other.len(), 6);
+    }
+
+    #[test]
+    fn quantize_to_millis() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in [100, 400, 600, 1_200, 1_499, 2_500, 3_000] {
+            tv.push_back(Duration::from_micros(i), i);
+        }
+
+        tv.quantize_time(Duration::from_millis(1));
+        assert!(tv.is_monotonic());
+        assert_eq!(
+            tv.iter().map(|i| (i.0.as_millis(), i.1)).collect::<Vec<_>>(),
+            vec![(0, 400), (1, 1_499), (3, 3_000)]
+        );
+    }
+
+    #[test]
+    fn value_range_single_pass() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.value_range(), None);
+
+        for (i, value) in [3.0, -1.5, 7.25, 0.0].into_iter().enumerate() {
+            tv.push_back(Duration::from_secs(i as u64), value);
+        }
+        assert_eq!(tv.value_range(), Some((-1.5, 7.25)));
+    }
+
+    #[test]
+    fn verify_detects_corruption() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.verify(), Ok(()));
+
+        tv.push_back(Duration::from_secs(1), ());
+        tv.push_back(Duration::from_secs(3), ());
+        assert_eq!(tv.verify(), Ok(()));
+
+        tv.push_back_unchecked(Duration::from_secs(2), ());
+        tv.push_back_unchecked(Duration::from_secs(1), ());
+        assert_eq!(tv.verify(), Err(2));
+
+        tv.push_back_unchecked(Duration::ZERO, ());
+        assert_eq!(tv.checked_duration(), None);
+        assert_eq!(tv.duration(), Duration::ZERO);
+    }
+
+    #[test]
+    fn sum_in_sub_window() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..6 {
+            tv.push_back(Duration::from_secs(i), i as f64);
+        }
+        let s = Duration::from_secs;
+
+        assert_eq!(tv.sum_in_range(s(1), s(4)), 6.0);
+        assert_eq!(tv.sum_in_range(s(0), s(10)), 15.0);
+        assert_eq!(tv.sum_in_range(s(4), s(1)), 0.0);
+    }
+
+    #[test]
+    fn custom_evict_policy() {
+        struct MaxLen(usize);
+
+        impl<T> EvictPolicy<T> for MaxLen {
+            fn should_evict(&self, buffer: &VecDeque<Item<T>>, _: Duration) -> usize {
+                (buffer.len() + 1).saturating_sub(self.0)
+            }
+        }
+
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_nanos(1)
+            .with_policy(MaxLen(3))
+            .build();
+        for i in 0..5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(tv.push_back(Duration::from_secs(9), 9).map(|i| i.1).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(tv.clone().push_back(Duration::from_secs(10), 10).count(), 1);
+
+        struct Window;
+
+        impl EvictPolicy<u64> for Window {
+            fn should_evict(&self, buffer: &VecDeque<Item<u64>>, new_back: Duration) -> usize {
+                let by_time = TimeLimit(Duration::from_secs(2)).should_evict(buffer, new_back);
+                by_time.max((buffer.len() + 1).saturating_sub(2))
+            }
+        }
+
+        let mut tv = TimeVec::<u64>::builder()
+            .with_policy(Window)
+            .build();
+        tv.push_back(Duration::from_secs(1), 1);
+        tv.push_back(Duration::from_secs(5), 5);
+        assert_eq!(tv.len(), 1);
+
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(100)
+            .with_policy(MaxLen(2))
+            .build();
+        let evicted = tv.insert_many((0..4).map(|i| (Duration::from_secs(i), i))).count();
+        assert_eq!((evicted, tv.len()), (2, 2));
+        tv.merge_iter([(Duration::from_secs(5), 5)]);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![3, 5]);
+        let other = TimeVec::from_parts(Duration::MAX, (6..9).map(|i| (Duration::from_secs(i), i)).collect());
+        tv.extend_from(&other);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![7, 8]);
+
+        assert!(tv.push_bounded(Duration::from_secs(9), 9, 2).is_ok());
+        assert_eq!(tv.push_bounded(Duration::from_secs(10), 10, 1).unwrap_err().0.1, 10);
+        assert_eq!(tv.len(), 2);
+    }
+
+    #[test]
+    fn iter_rebased_from_front() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.iter_rebased().len(), 0);
+
+        tv.push_back(Duration::from_secs(5), 5);
+        tv.push_back(Duration::from_secs(7), 7);
+        assert_eq!(
+            tv.iter_rebased().collect::<Vec<_>>(),
+            vec![(Duration::ZERO, &5), (Duration::from_secs(2), &7)]
+        );
+        assert_eq!(tv.iter_time().next(), Some(&Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn would_evict_dry_run() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_nanos(3)
+            .build();
+        for i in 0..5 {
+            tv.push_back(Duration::from_nanos(i), ());
+        }
+
+        let timestamp = Duration::from_nanos(7);
+        let expected = tv.would_evict(timestamp);
+        assert_eq!(expected, Some(3));
+        assert_eq!(tv.len(), 4);
+        assert_eq!(Some(tv.push_back(timestamp, ()).count()), expected);
+
+        assert_eq!(tv.would_evict(Duration::from_nanos(7)), None);
+        assert_eq!(tv.would_evict(Duration::from_nanos(8)), Some(1));
+
+        struct MaxLen(usize);
+
+        impl EvictPolicy<()> for MaxLen {
+            fn should_evict(&self, buffer: &VecDeque<Item<()>>, _: Duration) -> usize {
+                (buffer.len() + 1).saturating_sub(self.0)
+            }
+        }
+
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(100)
+            .with_policy(MaxLen(2))
+            .build();
+        tv.push_back(Duration::from_secs(0), ());
+        tv.push_back(Duration::from_secs(1), ());
+        assert_eq!(tv.would_evict(Duration::from_secs(2)), Some(1));
+        assert_eq!(tv.push_back(Duration::from_secs(2), ()).count(), 1);
+
+        let tv = TimeVec::<()>::builder()
+            .with_limit(Duration::ZERO)
+            .strict_window(true)
+            .build();
+        assert_eq!(tv.would_evict(Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn split_into_clusters() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(100)
+            .build();
+        for i in [0, 1, 2, 10, 11, 30, 31, 32] {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+        let original: Vec<_> = tv.iter().copied().collect();
+
+        let parts = tv.split_by_gap(Duration::from_secs(5));
+        assert_eq!(parts.iter().map(TimeVec::len).collect::<Vec<_>>(), vec![3, 2, 3]);
+        assert!(parts.iter().all(|p| p.limit == Duration::from_secs(100)));
+        assert_eq!(parts.iter().flat_map(|p| p.iter().copied()).collect::<Vec<_>>(), original);
+
+        let empty = TimeVec::<u64>::builder().build();
+        assert!(empty.split_by_gap(Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn map_in_place_doubles() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .with_capacity(8)
+            .build();
+        for i in 1..=3 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+        let capacity = tv.capacity();
+
+        tv.map_in_place(|v| *v *= 2);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![2, 4, 6]);
+        assert_eq!(tv.capacity(), capacity);
+        assert!(tv.is_monotonic());
+    }
+
+    #[test]
+    fn nearest_with_tolerance() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(100)
+            .build();
+        assert_eq!(tv.nearest(Duration::ZERO), None);
+
+        for i in [10, 20, 50] {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+        let s = Duration::from_secs;
+
+        assert_eq!(tv.nearest(s(0)).map(|i| i.1), Some(10));
+        assert_eq!(tv.nearest(s(16)).map(|i| i.1), Some(20));
+        assert_eq!(tv.nearest(s(15)).map(|i| i.1), Some(10));
+        assert_eq!(tv.nearest(s(99)).map(|i| i.1), Some(50));
+
+        assert_eq!(tv.nearest_within(s(35), s(5)), None);
+        assert_eq!(tv.nearest_within(s(48), s(2)).map(|i| i.1), Some(50));
+        assert_eq!(tv.nearest_within(s(5), s(5)).map(|i| i.1), Some(10));
+    }
+
+    #[test]
+    fn drain_stale_items() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(2)
+            .build();
+        for i in 1..=4 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        assert!(tv.drain_stale(Duration::from_secs(4)).is_empty());
+        assert_eq!(
+            tv.drain_stale(Duration::from_secs(5)),
+            vec![(Duration::from_secs(2), 2)]
+        );
+        assert_eq!(tv.drain_stale(Duration::from_secs(10)).len(), 2);
+        assert!(tv.is_empty());
+    }
+
+    #[test]
+    fn capacity_utilization_ratio() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.capacity_utilization(), 0.0);
+
+        tv.reserve_exact(8);
+        let half = tv.capacity() as u64 / 2;
+        for i in 0..half {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+        assert!((tv.capacity_utilization() - 0.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn push_with_now_evicts_by_clock() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(3)
+            .build();
+        for i in 1..=4 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        let evicted: Vec<_> = tv
+            .push_with_now(Duration::from_secs(5), 5, Duration::from_secs(8))
+            .unwrap()
+            .map(|i| i.1)
+            .collect();
+        assert_eq!(evicted, vec![1, 2, 3, 4]);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![5]);
+
+        assert!(tv.push_with_now(Duration::from_secs(5), 0, Duration::from_secs(9)).is_none());
+        assert_eq!(tv.len(), 1);
+        assert_eq!(tv.evict_now(Duration::from_secs(9)).count(), 1);
+        assert!(tv.is_empty());
+    }
+
+    #[test]
+    fn step_points_staircase() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert!(tv.to_step_points().is_empty());
+
+        tv.push_back(Duration::from_secs(1), 1.0);
+        assert_eq!(tv.to_step_points(), vec![(Duration::from_secs(1), 1.0)]);
+
+        tv.push_back(Duration::from_secs(2), 3.0);
+        assert_eq!(
+            tv.to_step_points(),
+            vec![
+                (Duration::from_secs(1), 1.0),
+                (Duration::from_secs(2), 1.0),
+                (Duration::from_secs(2), 3.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn resample_area_weighted() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(10)
+            .build();
+        tv.push_back(Duration::ZERO, 0.0);
+        assert!(tv.resample_area(Duration::from_secs(1)).is_empty());
+
+        tv.push_back(Duration::from_millis(1500), 10.0);
+        tv.push_back(Duration::from_millis(3500), 2.0);
+        tv.push_back(Duration::from_secs(4), 0.0);
+
+        assert_eq!(
+            tv.resample_area(Duration::from_secs(1)),
+            vec![
+                (Duration::from_secs(0), 0.0),
+                (Duration::from_secs(1), 5.0),
+                (Duration::from_secs(2), 10.0),
+                (Duration::from_secs(3), 6.0),
+            ]
+        );
+        assert_eq!(
+            tv.resample_area(Duration::from_millis(2500)),
+            vec![
+                (Duration::ZERO, 4.0),
+                (Duration::from_millis(2500), (10.0 + 1.0) / 1.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn index_lookups() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        assert_eq!(tv.first_index_after(Duration::ZERO), None);
+        assert_eq!(tv.last_index_before(Duration::MAX), None);
+
+        for i in [2, 4, 6] {
+            tv.push_back(Duration::from_secs(i), ());
+        }
+        let s = Duration::from_secs;
+
+        assert_eq!(tv.first_index_after(s(1)), Some(0));
+        assert_eq!(tv.last_index_before(s(1)), None);
+        assert_eq!(tv.first_index_after(s(4)), Some(2));
+        assert_eq!(tv.last_index_before(s(4)), Some(0));
+        assert_eq!(tv.first_index_after(s(5)), Some(2));
+        assert_eq!(tv.last_index_before(s(5)), Some(1));
+        assert_eq!(tv.first_index_after(s(7)), None);
+        assert_eq!(tv.last_index_before(s(7)), Some(2));
+    }
+
+    #[test]
+    fn drain_keep_newest_returns_dropped() {
+        let mut tv = TimeVec::<u64>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        let dropped = tv.drain_keep_newest(2);
+        assert_eq!(dropped.iter().map(|i| i.1).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(tv.iter_data().copied().collect::<Vec<_>>(), vec![3, 4]);
+        assert!(tv.drain_keep_newest(5).is_empty());
+    }
+
+    #[test]
+    fn coverage_predicate() {
+        let mut tv = TimeVec::<()>::builder()
+            .with_limit_secs(10)
+            .build();
+        for i in 0..=3 {
+            tv.push_back(Duration::from_nanos(i), ());
+        }
+
+        assert!(tv.has_coverage(Duration::from_nanos(2)));
+        assert!(tv.has_coverage(Duration::from_nanos(3)));
+        assert!(!tv.has_coverage(Duration::from_nanos(4)));
+    }
+
+    #[test]
+    fn autocorrelation_at_period() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(100)
+            .build();
+        for i in 0..40 {
+            let phase = i as f64 / 8.0 * std::f64::consts::TAU;
+            tv.push_back(Duration::from_millis(i * 250), phase.sin());
+        }
+
+        assert!(tv.autocorrelation(Duration::from_secs(2)).unwrap() > 0.99);
+        assert!(tv.autocorrelation(Duration::from_secs(1)).unwrap() < -0.99);
+        assert_eq!(tv.autocorrelation(Duration::from_secs(10)), None);
+    }
+
+    #[test]
+    fn rle_runs() {
+        let mut tv = TimeVec::<char>::new(Duration::from_secs(10), 4);
+        tv.push_back(Duration::from_secs(0), 'a');
+        tv.push_back(Duration::from_secs(1), 'a');
+        tv.push_back(Duration::from_secs(2), 'b');
+        tv.push_back(Duration::from_secs(4), 'a');
+
+        let secs = Duration::from_secs;
+        assert_eq!(
+            tv.rle(),
+            vec![(secs(0), secs(2), 'a'), (secs(2), secs(4), 'b'), (secs(4), secs(4), 'a')]
+        );
+        assert!(TimeVec::<char>::new(secs(1), 0).rle().is_empty());
+    }
+
+    #[test]
+    fn unwind_safe_with_sink_and_policy() {
+        fn assert_unwind_safe<V: UnwindSafe + RefUnwindSafe>(_: &V) {}
+        struct Keep;
+        impl EvictPolicy<u8> for Keep {
+            fn should_evict(&self, _: &VecDeque<Item<u8>>, _: Duration) -> usize {
+                0
+            }
+        }
+        let mut tv = TimeVec::builder()
+            .with_limit_secs(1)
+            .with_sink(Box::new(|_| {}))
+            .with_policy(Keep)
+            .build();
+        assert_unwind_safe(&tv);
+        let result = std::panic::catch_unwind(move || {
+            tv.push_back(Duration::ZERO, 1u8);
+            tv.len()
+        });
+        assert_eq!(result.ok(), Some(1));
+    }
+
+    #[test]
+    fn builder_coarse_limits() {
+        assert_eq!(
+            TimeVecBuilder::<()>::default().with_limit_mins(5).limit,
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            TimeVecBuilder::<()>::default().with_limit_hours(2).limit,
+            Some(Duration::from_secs(7200))
+        );
+        assert_eq!(
+            TimeVecBuilder::<()>::default().with_limit_secs_f64(1.5).limit,
+            Some(Duration::from_millis(1500))
+        );
+        for (value, limit) in [
+            (-1.0, Duration::ZERO),
+            (f64::NAN, Duration::ZERO),
+            (f64::INFINITY, Duration::MAX),
+            (1e300, Duration::MAX),
+        ] {
+            assert_eq!(TimeVecBuilder::<()>::default().with_limit_secs_f64(value).limit, Some(limit));
+        }
+    }
+
+    #[test]
+    fn pairs_with_dt() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 3);
+        assert_eq!(tv.iter_pairs_dt().count(), 0);
+        tv.push_back(Duration::from_millis(0), 1);
+        assert_eq!(tv.iter_pairs_dt().count(), 0);
+        tv.push_back(Duration::from_millis(500), 2);
+        tv.push_back(Duration::from_millis(2000), 3);
+
+        let pairs: Vec<_> = tv.iter_pairs_dt().collect();
+        assert_eq!(pairs, vec![(0.5, &1, &2), (1.5, &2, &3)]);
+    }
+
+    #[test]
+    fn fresh_iter_view() {
+        let mut tv = TimeVec::new(Duration::from_secs(2), 4);
+        for i in 0..3 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        assert_eq!(tv.fresh_iter(Duration::from_secs(2)).count(), 3);
+        let fresh: Vec<_> = tv.fresh_iter(Duration::from_secs(3)).map(|i| i.1).collect();
+        assert_eq!(fresh, vec![1, 2]);
+        assert_eq!(tv.fresh_iter(Duration::from_secs(5)).count(), 0);
+        assert_eq!(tv.len(), 3);
+    }
+
+    #[test]
+    fn merge_external_sorted_stream() {
+        let mut tv = TimeVec::new(Duration::from_secs(4), 4);
+        tv.push_back(Duration::from_secs(1), 'a');
+        tv.push_back(Duration::from_secs(3), 'c');
+
+        let stream = [0, 2, 3, 5].into_iter().map(|s| (Duration::from_secs(s), 'x'));
+        let evicted: Vec<_> = tv.merge_iter(stream).collect();
+
+        assert_eq!(evicted, vec![(Duration::from_secs(0), 'x')]);
+        let items: Vec<_> = tv.iter().map(|i| (i.0.as_secs(), i.1)).collect();
+        assert_eq!(items, vec![(1, 'a'), (2, 'x'), (3, 'c'), (5, 'x')]);
+    }
+
+    #[test]
+    fn zip_interpolated_offset_ramps() {
+        let mut a = TimeVec::new(Duration::from_secs(10), 5);
+        let mut b = TimeVec::new(Duration::from_secs(10), 5);
+        for i in 0..5u64 {
+            a.push_back(Duration::from_secs(i), i as f64);
+            b.push_back(Duration::from_millis(500 + i * 1000), 10.0 * i as f64);
+        }
+
+        let pairs = a.zip_interpolated(&b);
+        let expected: Vec<_> = (1..5u64)
+            .map(|i| (Duration::from_secs(i), i as f64, 10.0 * i as f64 - 5.0))
+            .collect();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn parts_round_trip() {
+        let mut tv = TimeVec::new(Duration::from_secs(3), 2);
+        tv.push_back(Duration::from_secs(1), 'a');
+        tv.push_back(Duration::from_secs(2), 'b');
+
+        let (limit, buffer) = tv.into_parts();
+        assert_eq!(limit, Duration::from_secs(3));
+        assert_eq!(buffer.len(), 2);
+
+        let tv = TimeVec::from_parts(limit, buffer);
+        assert_eq!(tv.limit, Duration::from_secs(3));
+        assert_eq!(tv.iter_data().collect::<String>(), "ab");
+    }
+
+    #[test]
+    fn counts_per_interval_with_gap() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 6);
+        for ms in [0, 200, 400, 1500, 3100, 3900] {
+            tv.push_back(Duration::from_millis(ms), ());
+        }
+
+        let counts = tv.counts_per_interval(Duration::from_secs(1));
+        let secs = Duration::from_secs;
+        assert_eq!(counts, vec![(secs(0), 3), (secs(1), 1), (secs(2), 0), (secs(3), 2)]);
+        assert!(TimeVec::<()>::new(secs(1), 0).counts_per_interval(secs(1)).is_empty());
+    }
+
+    #[test]
+    fn push_bounded_backpressure() {
+        let mut tv = TimeVec::new(Duration::from_secs(2), 2);
+        assert!(tv.push_bounded(Duration::from_secs(0), 'a', 2).is_ok());
+        assert!(tv.push_bounded(Duration::from_secs(1), 'b', 2).is_ok());
+
+        let err = tv.push_bounded(Duration::from_secs(2), 'c', 2).unwrap_err();
+        assert_eq!(err, CapacityError((Duration::from_secs(2), 'c')));
+        assert_eq!(tv.len(), 2);
+
+        let evicted: Vec<_> = tv.push_bounded(Duration::from_secs(3), 'd', 2).unwrap().collect();
+        assert_eq!(evicted, vec![(Duration::from_secs(0), 'a')]);
+        assert_eq!(tv.iter_data().collect::<String>(), "bd");
+    }
+
+    #[test]
+    fn contiguous_or_owned_snapshot() {
+        let mut tv = TimeVec::new(Duration::from_secs(3), 4);
+        for i in 0..3 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+        assert!(matches!(tv.contiguous_or_owned(), Cow::Borrowed(_)));
+
+        let mut i = 3;
+        while tv.buffer.as_slices().1.is_empty() {
+            tv.push_back(Duration::from_secs(i), i);
+            i += 1;
+        }
+        let snapshot = tv.contiguous_or_owned();
+        assert!(matches!(snapshot, Cow::Owned(_)));
+        let expected: Vec<_> = tv.iter().cloned().collect();
+        assert_eq!(snapshot.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn strict_zero_window() {
+        let mut lenient = TimeVec::<char>::builder()
+            .with_limit(Duration::ZERO)
+            .build();
+        assert_eq!(lenient.push_back(Duration::from_secs(1), 'a').count(), 0);
+        assert_eq!(lenient.len(), 1);
+
+        let mut strict = TimeVec::<char>::builder()
+            .with_limit(Duration::ZERO)
+            .strict_window(true)
+            .build();
+        let rejected: Vec<_> = strict.push_back(Duration::from_secs(1), 'a').collect();
+        assert_eq!(rejected, vec![(Duration::from_secs(1), 'a')]);
+        assert!(strict.push_back_checked(Duration::from_secs(2), 'b').is_none());
+        assert!(!strict.push_report(Duration::from_secs(3), 'c').accepted);
+        assert!(strict.is_empty());
+        assert_eq!(strict.total_evicted(), 0);
+
+        let sunk = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = sunk.clone();
+        let mut strict = TimeVec::<char>::builder()
+            .with_limit(Duration::ZERO)
+            .strict_window(true)
+            .with_sink(Box::new(move |_| {
+                counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            }))
+            .build();
+        let rejected: Vec<_> = strict.push_back(Duration::from_secs(1), 'a').collect();
+        assert_eq!(rejected, vec![(Duration::from_secs(1), 'a')]);
+        let rejected: Vec<_> = strict
+            .insert_many([(Duration::from_secs(3), 'c'), (Duration::from_secs(2), 'b')])
+            .collect();
+        assert_eq!(rejected, vec![(Duration::from_secs(2), 'b'), (Duration::from_secs(3), 'c')]);
+        assert_eq!(strict.merge_iter([(Duration::from_secs(4), 'd')]).count(), 1);
+        assert!(strict.is_empty());
+        assert_eq!(strict.total_evicted(), 0);
+        assert_eq!(sunk.load(std::sync::atomic::Ordering::Relaxed), 0);
+
+        let mut strict = TimeVec::<char>::builder()
+            .with_limit_secs(1)
+            .strict_window(true)
+            .build();
+        strict.push_back(Duration::from_secs(1), 'a');
+        strict.push_back(Duration::from_secs(2), 'b');
+        assert_eq!(strict.len(), 2);
+    }
+
+    #[test]
+    fn tracked_extremes() {
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(2)
+            .track_extremes()
+            .build();
+        assert_eq!(tv.current_max(), None);
+
+        for (t, v) in [(0, 5.0), (1, 1.0), (2, 3.0)] {
+            tv.push_back(Duration::from_secs(t), v);
+        }
+        assert_eq!(tv.current_max(), Some(5.0));
+        assert_eq!(tv.current_min(), Some(1.0));
+
+        tv.push_back(Duration::from_secs(3), 2.0);
+        assert_eq!(tv.current_max(), Some(3.0));
+        assert_eq!(tv.current_min(), Some(1.0));
+
+        tv.push_back(Duration::from_secs(4), 0.5);
+        assert_eq!(tv.current_max(), Some(3.0));
+        assert_eq!(tv.current_min(), Some(0.5));
+
+        tv.pop_front();
+        assert_eq!(tv.current_max(), Some(2.0));
+        assert_eq!(tv.current_max(), tv.value_range().map(|(_, max)| max));
+    }
+
+    #[test]
+    fn partition_by_parity() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 6);
+        for i in 0..6 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        let (even, odd) = tv.partition_into(|item| item.1 % 2 == 0);
+        assert_eq!(even.iter_data().copied().collect::<Vec<_>>(), vec![0, 2, 4]);
+        assert_eq!(odd.iter_data().copied().collect::<Vec<_>>(), vec![1, 3, 5]);
+        assert!(even.is_monotonic() && odd.is_monotonic());
+        assert_eq!(odd.limit, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn sparkline_levels() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 8);
+        assert_eq!(tv.sparkline(), "");
+        for i in 0..8 {
+            tv.push_back(Duration::from_secs(i), i as f64);
+        }
+        let line = tv.sparkline();
+        assert_eq!(line.chars().count(), tv.len());
+        assert_eq!(line, "▁▂▃▄▅▆▇█");
+
+        tv.map_in_place(|v| *v = 1.0);
+        assert_eq!(tv.sparkline(), "▅".repeat(8));
+    }
+
+    #[test]
+    fn replace_value_at_timestamp() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 3);
+        for (t, v) in [(1, 'a'), (2, 'b'), (3, 'c')] {
+            tv.push_back(Duration::from_secs(t), v);
+        }
+
+        assert_eq!(tv.replace_at(Duration::from_secs(2), 'x'), Some('b'));
+        assert_eq!(tv.replace_at(Duration::from_millis(2500), 'y'), None);
+        assert_eq!(tv.iter_data().collect::<String>(), "axc");
+    }
+
+    #[test]
+    fn take_leaves_empty() {
+        let mut tv = TimeVec::new(Duration::from_secs(5), 4);
+        tv.push_back(Duration::from_secs(1), 'a');
+        tv.push_back(Duration::from_secs(2), 'b');
+
+        let taken = tv.take();
+        assert!(tv.is_empty());
+        assert_eq!(tv.limit, Duration::from_secs(5));
+        assert_eq!(taken.limit, Duration::from_secs(5));
+        assert_eq!(taken.iter_data().collect::<String>(), "ab");
+
+        tv.push_back(Duration::from_secs(1), 'c');
+        assert_eq!(tv.len(), 1);
+    }
+
+    #[test]
+    fn iter_value_changes() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 5);
+        for (t, v) in "aabba".chars().enumerate() {
+            tv.push_back(Duration::from_secs(t as u64), v);
+        }
+
+        let changes: Vec<_> = tv.iter_changes().map(|i| i.0.as_secs()).collect();
+        assert_eq!(changes, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn reserve_from_observed_rate() {
+        let mut tv = TimeVec::new(Duration::from_secs(1), 0);
+        tv.push_back(Duration::ZERO, ());
+        tv.reserve_for_observed_rate();
+        assert!(tv.capacity() < 11);
+
+        for i in 1..3 {
+            tv.push_back(Duration::from_millis(i * 100), ());
+        }
+        tv.reserve_for_observed_rate();
+        let capacity = tv.capacity();
+        assert!(capacity >= 11);
+
+        for i in 3..=10 {
+            tv.push_back(Duration::from_millis(i * 100), ());
+        }
+        assert_eq!(tv.len(), 11);
+        assert_eq!(tv.capacity(), capacity);
+    }
+
+    #[test]
+    fn peak_hold_decay() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 3);
+        assert_eq!(tv.peak_hold(1.0), None);
+
+        tv.push_back(Duration::from_secs(0), 10.0);
+        tv.push_back(Duration::from_secs(1), 2.0);
+        assert_eq!(tv.peak_hold(1.0), Some(9.0));
+
+        tv.push_back(Duration::from_secs(5), 6.0);
+        assert_eq!(tv.peak_hold(1.0), Some(6.0));
+        assert_eq!(tv.peak_hold(0.0), Some(10.0));
+    }
+
+    #[test]
+    fn split_view_halves() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 5);
+        for i in 0..5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        let (left, right) = tv.split_view(3);
+        assert_eq!((left.len(), right.len()), (3, 2));
+        assert_eq!(right[0].1, 3);
+
+        let (left, right) = tv.split_view(9);
+        assert_eq!((left.len(), right.len()), (5, 0));
+        assert_eq!(tv.len(), 5);
+    }
+
+    #[test]
+    fn try_build_validation() {
+        let secs = Duration::from_secs;
+        let unsorted = TimeVec::builder()
+            .with_limit_secs(10)
+            .with_data(vec![(secs(1), 'a'), (secs(3), 'b'), (secs(2), 'c')])
+            .try_build();
+        assert_eq!(unsorted.unwrap_err(), BuildError::UnsortedData(2));
+
+        let strict = TimeVec::builder()
+            .with_limit(Duration::ZERO)
+            .strict_window(true)
+            .with_data(vec![(secs(1), 'a')])
+            .try_build();
+        assert_eq!(strict.unwrap_err(), BuildError::DataInStrictZeroWindow);
+
+        let tv = TimeVec::builder()
+            .with_limit_secs(10)
+            .with_data(vec![(secs(1), 'a'), (secs(2), 'b')])
+            .try_build()
+            .unwrap();
+        assert_eq!(tv.len(), 2);
+    }
+
+    #[test]
+    fn trailing_time_windows() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 4);
+        for t in [0, 1, 3, 4] {
+            tv.push_back(Duration::from_secs(t), t);
+        }
+
+        let windows: Vec<Vec<u64>> = tv
+            .time_windows(Duration::from_secs(1))
+            .map(|w| w.into_iter().map(|i| i.1).collect())
+            .collect();
+        assert_eq!(windows, vec![vec![0], vec![0, 1], vec![3], vec![3, 4]]);
+    }
+
+    #[test]
+    fn trim_oldest_fraction() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 4);
+        for i in 0..4 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        assert!(tv.trim_fraction(-1.0).is_empty());
+        let trimmed: Vec<_> = tv.trim_fraction(0.5).into_iter().map(|i| i.1).collect();
+        assert_eq!(trimmed, vec![0, 1]);
+        assert_eq!(tv.len(), 2);
+        assert_eq!(tv.trim_fraction(0.1).len(), 1);
+        assert_eq!(tv.trim_fraction(5.0).len(), 1);
+        assert!(tv.is_empty());
+    }
+
+    #[test]
+    fn builder_limit_from_span() {
+        let secs = Duration::from_secs;
+        let tv = TimeVec::builder()
+            .with_limit_from_span()
+            .with_data(vec![(secs(2), 'a'), (secs(5), 'b'), (secs(9), 'c')])
+            .build();
+        assert_eq!(tv.limit, secs(7));
+        assert_eq!(tv.len(), 3);
+
+        let tv = TimeVec::builder()
+            .with_limit_secs(3)
+            .with_data(vec![(secs(2), 'a')])
+            .with_limit_from_span()
+            .build();
+        assert_eq!(tv.limit, Duration::ZERO);
+    }
+
+    #[test]
+    fn bulk_pops() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 5);
+        for i in 0..5 {
+            tv.push_back(Duration::from_secs(i), i);
+        }
+
+        let front: Vec<_> = tv.pop_front_n(2).into_iter().map(|i| i.1).collect();
+        assert_eq!(front, vec![0, 1]);
+        let back: Vec<_> = tv.pop_back_n(2).into_iter().map(|i| i.1).collect();
+        assert_eq!(back, vec![3, 4]);
+
+        assert_eq!(tv.pop_back_n(5).len(), 1);
+        assert!(tv.pop_front_n(5).is_empty());
+        tv.push_back(Duration::from_secs(0), 0);
+        assert_eq!(tv.pop_front_n(5).len(), 1);
+    }
+
+    #[test]
+    fn positional_fraction() {
+        let mut tv = TimeVec::new(Duration::from_secs(10), 5);
+        assert_eq!(tv.value_at_fraction(0.5), None);
+        for (t, v) in "abcde".chars().enumerate() {
+            tv.push_back(Duration::from_secs(t as u64), v);
+        }
+
+        assert_eq!(tv.value_at_fraction(0.5), Some(&'c'));
+        assert_eq!(tv.value_at_fraction(-1.0), Some(&'a'));
+        assert_eq!(tv.value_at_fraction(0.3), Some(&'b'));
+        assert_eq!(tv.value_at_fraction(2.0), Some(&'e'));
+    }
+
+    #[test]
+    fn merge_downsampled_matches_two_step() {
+        let mut a = TimeVec::new(Duration::from_secs(60), 10);
+        let mut b = TimeVec::new(Duration::from_secs(60), 10);
+        for i in 0..10u64 {
+            a.push_back(Duration::from_millis(i * 300), i as f64);
+            b.push_back(Duration::from_millis(100 + i * 450), 10.0 - i as f64);
+        }
+        b.push_back(Duration::from_millis(4500), 99.0);
+        a.push_back(Duration::from_millis(4500), 1.0);
+
+        let interval = Duration::from_secs(1);
+        let reference = TimeVec::concat([a.clone(), b.clone()], Duration::MAX).downsample_mean(interval);
+        let merged = TimeVec::merge_downsampled([a, b], interval);
+
+        assert_eq!(merged.len(), 5);
+        assert_eq!(merged, reference);
+    }
+
+    #[test]
+    fn tracked_extremes_follow_every_insert_path() {
+        fn check(tv: &TimeVec<f64>) {
+            assert_eq!(tv.current_min(), tv.value_range().map(|(min, _)| min));
+            assert_eq!(tv.current_max(), tv.value_range().map(|(_, max)| max));
+        }
+
+        let secs = Duration::from_secs;
+        let mut tv = TimeVec::<f64>::builder()
+            .with_limit_secs(100)
+            .track_extremes()
+            .with_data(vec![(secs(0), 1.0), (secs(1), 2.0)])
+            .build();
+        check(&tv);
+
+        let mut with_nan = TimeVec::<f64>::builder()
+            .with_limit_secs(100)
+            .track_extremes()
+            .build();
+        with_nan.push_back(secs(0), f64::NAN);
+        assert!(with_nan.current_min().is_some_and(f64::is_nan));
+        with_nan.push_back(secs(1), 3.0);
+        with_nan.push_back(secs(2), 5.0);
+        assert_eq!(with_nan.current_min(), Some(3.0));
+        assert_eq!(with_nan.current_max(), Some(5.0));
+        check(&with_nan);
+
+        tv.insert_many([(secs(3), 50.0), (secs(2), -3.0)]);
+        assert_eq!(tv.current_max(), Some(50.0));
+        check(&tv);
+
+        tv.push_with_now(secs(4), 100.0, secs(4));
+        assert_eq!(tv.current_max(), Some(100.0));
+        check(&tv);
+
+        tv.merge_iter([(secs(5), -7.0)]);
+        assert_eq!(tv.current_min(), Some(-7.0));
+        check(&tv);
+
+        let mut other = TimeVec::new(secs(100), 1);
+        other.push_back(secs(6), 900.0);
+        tv.extend_from(&other);
+        assert_eq!(tv.current_max(), Some(900.0));
+        check(&tv);
+
+        tv.pop_back();
+        check(&tv);
+        tv.push_back(secs(7), 0.0);
+        check(&tv);
+
+        tv.retain(|item| item.1 < 40.0);
+        check(&tv);
+        tv.pop_front();
+        check(&tv);
+        tv.drain(1..2);
+        check(&tv);
+        tv.push_back(secs(8), f64::NAN);
+        tv.drop_nan();
+        check(&tv);
+
+        tv.quantize_time(secs(100));
+        assert_eq!(tv.len(), 1);
+        check(&tv);
+
+        tv.clear();
+        check(&tv);
+        tv.push_back(secs(9), 4.0);
+        assert_eq!(tv.current_max(), Some(4.0));
+        check(&tv);
+    }
+
+    #[test]
+    fn reserve_from_observed_rate_is_bounded() {
+        let mut tv = TimeVec::new(Duration::MAX, 2);
+        tv.push_back(Duration::ZERO, ());
+        tv.push_back(Duration::from_secs(1), ());
+        tv.reserve_for_observed_rate();
+        assert_eq!(tv.len(), 2);
+
+        let mut tv = TimeVec::new(Duration::from_secs(365 * 24 * 60 * 60), 2);
+        tv.push_back(Duration::ZERO, 0u64);
+        tv.push_back(Duration::from_nanos(1), 1);
+        tv.reserve_for_observed_rate();
+        assert_eq!(tv.len(), 2);
     }
 }